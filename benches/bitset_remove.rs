@@ -0,0 +1,52 @@
+use ezcp::bitset::BitsetDomain;
+use ezcp::domain::Domain;
+use ezcp::solver::SolverState;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+const DOMAIN_SIZE: i64 = 1 << 20;
+const ROUNDS: usize = 20;
+
+// removes values from the low end of the domain one by one
+fn remove_from_low_end(state: Rc<RefCell<SolverState>>) -> u128 {
+    let mut domain = BitsetDomain::new(state, 0, DOMAIN_SIZE - 1);
+    let start = Instant::now();
+    for x in 0..DOMAIN_SIZE - 1 {
+        domain.remove(x);
+    }
+    assert_eq!(domain.get_lb(), DOMAIN_SIZE - 1);
+    start.elapsed().as_micros()
+}
+
+// removes every other value first, then the remaining values from the low end
+fn remove_sparse_then_low_end(state: Rc<RefCell<SolverState>>) -> u128 {
+    let mut domain = BitsetDomain::new(state, 0, DOMAIN_SIZE - 1);
+    let start = Instant::now();
+    for x in (1..DOMAIN_SIZE).step_by(2) {
+        domain.remove(x);
+    }
+    for x in (0..DOMAIN_SIZE - 2).step_by(2) {
+        domain.remove(x);
+    }
+    assert_eq!(domain.get_lb(), DOMAIN_SIZE - 2);
+    start.elapsed().as_micros()
+}
+
+fn main() {
+    let state = Rc::new(RefCell::new(SolverState::new()));
+    let mut low_end = 0;
+    let mut sparse = 0;
+    for _ in 0..ROUNDS {
+        low_end += remove_from_low_end(state.clone());
+        sparse += remove_sparse_then_low_end(state.clone());
+    }
+    println!(
+        "remove from low end: {} us per round",
+        low_end / ROUNDS as u128
+    );
+    println!(
+        "sparse removal, then low end: {} us per round",
+        sparse / ROUNDS as u128
+    );
+}
@@ -0,0 +1,60 @@
+use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::objective_function::{AllDifferentSumBound, ObjectiveFunction};
+use ezcp::solver::Solver;
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::Variable;
+use ezcp::variable_selector::FirstFailVariableSelector;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+const N: usize = 7;
+const ROUNDS: usize = 5;
+
+struct SumObjective {
+    vars: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl ObjectiveFunction for SumObjective {
+    fn eval(&self) -> i64 {
+        self.vars.iter().map(|v| v.borrow().value()).sum()
+    }
+
+    fn bound(&self) -> i64 {
+        self.vars.iter().map(|v| v.borrow().get_lb()).sum()
+    }
+}
+
+// minimizes the sum of N pairwise different variables
+fn minimize_sum(with_bound: bool) -> u128 {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let vars: Vec<_> = (0..N)
+        .map(|i| solver.new_variable(0, 3 * N as i64, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    if with_bound {
+        solver.add_objective_bound(Box::new(AllDifferentSumBound::new(vars.clone())));
+    }
+    solver.add_objective(Box::new(SumObjective { vars }));
+    let start = Instant::now();
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), (N * (N - 1) / 2) as i64);
+    start.elapsed().as_micros()
+}
+
+fn main() {
+    let mut plain = 0;
+    let mut bounded = 0;
+    for _ in 0..ROUNDS {
+        plain += minimize_sum(false);
+        bounded += minimize_sum(true);
+    }
+    println!("sum of lower bounds: {} us per round", plain / ROUNDS as u128);
+    println!(
+        "with alldifferent bound: {} us per round",
+        bounded / ROUNDS as u128
+    );
+}
@@ -0,0 +1,98 @@
+/* This program solves the assignment problem:
+ * assign n workers to n tasks (one task per worker)
+ * minimizing the total cost.
+ *
+ * Single command line argument:
+ * path to file with the following format:
+ * n
+ * n lines with n integers each, j-th number in i-th line is the cost of assigning worker i to task j
+ *
+ * Use sample.txt for example.
+ *
+ * Output format:
+ * optimal total cost
+ * n pairs (worker, task)
+ */
+use ezcp::linear::LinearInequalityConstraint;
+use ezcp::objective_function::LinearObjective;
+use ezcp::solver::Solver;
+use ezcp::value_selector::MaxValueSelector;
+use ezcp::variable::Variable;
+use ezcp::variable_selector::FirstFailVariableSelector;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::rc::Rc;
+
+fn read_costs(filename: &str) -> Vec<Vec<i64>> {
+    let file = File::open(filename).unwrap();
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines().map(|l| l.unwrap());
+    let n = lines.next().unwrap().trim().parse::<usize>().unwrap();
+    let mut cost = Vec::with_capacity(n);
+    for _ in 0..n {
+        let row: Vec<i64> = lines
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .map(|x| x.parse::<i64>().unwrap())
+            .collect();
+        assert!(row.len() == n);
+        cost.push(row);
+    }
+    cost
+}
+
+// sum vars = 1
+fn exactly_one(solver: &mut Solver, vars: Vec<Rc<RefCell<Variable>>>) {
+    let n = vars.len();
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vars.clone(),
+        vec![1; n],
+        1,
+    )));
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vars,
+        vec![-1; n],
+        -1,
+    )));
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let cost = read_costs(&args[1]);
+    let n = cost.len();
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MaxValueSelector {}),
+    );
+    // assigned[i][j] = 1 iff worker i does task j
+    let mut assigned = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut row = Vec::with_capacity(n);
+        for j in 0..n {
+            row.push(solver.new_variable(0, 1, format!("assigned_{}_{}", i, j)));
+        }
+        assigned.push(row);
+    }
+    for (i, row) in assigned.iter().enumerate() {
+        exactly_one(&mut solver, row.clone());
+        exactly_one(&mut solver, (0..n).map(|k| assigned[k][i].clone()).collect());
+    }
+    let objective = LinearObjective::new(
+        assigned.iter().flatten().cloned().collect(),
+        cost.iter().flatten().cloned().collect(),
+    );
+    solver.add_objective(Box::new(objective));
+    assert!(solver.solve());
+    // after solve() returns, the best solution found is assigned to the variables
+    println!("{}", solver.get_objective());
+    for (i, row) in assigned.iter().enumerate() {
+        for (j, var) in row.iter().enumerate() {
+            if var.borrow().value() == 1 {
+                println!("{} {}", i, j);
+            }
+        }
+    }
+}
@@ -13,22 +13,53 @@ use std::boxed::Box;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+fn fail(msg: String) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+fn parse_line<T: std::str::FromStr>(line: Option<String>, what: &str) -> T {
+    let Some(line) = line else {
+        fail(format!("Unexpected end of file, expected {}", what));
+    };
+    line.trim()
+        .parse::<T>()
+        .unwrap_or_else(|_| fail(format!("Invalid {}: {}", what, line)))
+}
+
 // read dataset in BPP format
 fn read_dataset(filename: &str) -> (Vec<i64>, i64) {
-    let file = File::open(filename).unwrap();
+    let file = File::open(filename).unwrap_or_else(|e| fail(format!("Cannot open {}: {}", filename, e)));
     let reader = BufReader::new(file);
-    let mut lines = reader.lines().map(|l| l.unwrap());
-    let n_items = lines.next().unwrap().parse::<usize>().unwrap();
-    let capacity = lines.next().unwrap().parse::<i64>().unwrap();
+    let mut lines = reader
+        .lines()
+        .map(|l| l.unwrap_or_else(|e| fail(format!("Cannot read {}: {}", filename, e))))
+        .filter(|l| !l.trim().is_empty());
+    let n_items = parse_line::<usize>(lines.next(), "number of items");
+    let capacity = parse_line::<i64>(lines.next(), "bin capacity");
     let mut items = Vec::with_capacity(n_items);
     for l in lines {
-        items.push(l.parse::<i64>().unwrap());
+        let weight = parse_line::<i64>(Some(l), "item weight");
+        if weight < 0 || weight > capacity {
+            fail(format!("Item weight {} does not fit in a bin of capacity {}", weight, capacity));
+        }
+        items.push(weight);
+    }
+    if items.len() != n_items {
+        eprintln!(
+            "Warning: header declares {} items, but file contains {}",
+            n_items,
+            items.len()
+        );
     }
     (items, capacity)
 }
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        fail(format!("Usage: {} <dataset>", args[0]));
+    }
     let (items, capacity) = read_dataset(&args[1]);
     let create_solver = |bins: i64| {
         let mut solver = Solver::new(
@@ -0,0 +1,111 @@
+/* This program colors vertices of a graph with at most k colors
+ * so that adjacent vertices get different colors,
+ * no color is used more than cap times,
+ * and the number of colors used is minimal.
+ * Colors are tried in a fixed global order: the solver
+ * puts color 0 wherever it can before trying color 1, and so on.
+ *
+ * Single command line argument:
+ * path to file with the following format:
+ * n m k cap
+ * m lines with two integers each, describing edge (u, v), 0 <= u, v < n
+ *
+ * Use sample.txt for example (Petersen graph).
+ *
+ * Output format:
+ * number of colors used
+ * color of each vertex
+ * or "no coloring" if there is no coloring satisfying the limits
+ */
+use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::gcc::GlobalCardinalityConstraint;
+use ezcp::objective_function::ObjectiveFunction;
+use ezcp::solver::Solver;
+use ezcp::value_selector::ValueOrderValueSelector;
+use ezcp::variable::Variable;
+use ezcp::variable_selector::ValueOrderVariableSelector;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::rc::Rc;
+
+// number of colors used, colors are 0, 1, ... so this is 1 + the largest color
+struct ColorCount {
+    color: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl ObjectiveFunction for ColorCount {
+    fn eval(&self) -> i64 {
+        1 + self.color.iter().map(|v| v.borrow().value()).max().unwrap()
+    }
+
+    fn bound(&self) -> i64 {
+        1 + self.color.iter().map(|v| v.borrow().get_lb()).max().unwrap()
+    }
+}
+
+fn read_graph(filename: &str) -> (usize, Vec<(usize, usize)>, i64, usize) {
+    let file = File::open(filename).unwrap();
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines().map(|l| l.unwrap());
+    let header: Vec<usize> = lines
+        .next()
+        .unwrap()
+        .split_whitespace()
+        .map(|x| x.parse::<usize>().unwrap())
+        .collect();
+    assert!(header.len() == 4);
+    let (n, m, k, cap) = (header[0], header[1], header[2] as i64, header[3]);
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let edge: Vec<usize> = lines
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .map(|x| x.parse::<usize>().unwrap())
+            .collect();
+        assert!(edge.len() == 2 && edge[0] < n && edge[1] < n);
+        edges.push((edge[0], edge[1]));
+    }
+    (n, edges, k, cap)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let (n, edges, k, cap) = read_graph(&args[1]);
+    let colors: Vec<i64> = (0..k).collect();
+    let mut solver = Solver::new(
+        Box::new(ValueOrderVariableSelector::new(colors.clone())),
+        Box::new(ValueOrderValueSelector::new(colors)),
+    );
+    let mut color = Vec::with_capacity(n);
+    for i in 0..n {
+        // colors can be renamed so that vertex i uses one of the first i + 1 colors
+        let ub = std::cmp::min(k - 1, i as i64);
+        color.push(solver.new_variable(0, ub, format!("color_{}", i)));
+    }
+    for (u, v) in edges {
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+            color[u].clone(),
+            color[v].clone(),
+        ])));
+    }
+    let card: HashMap<i64, usize> = (0..k).map(|c| (c, cap)).collect();
+    solver.add_constraint(Box::new(GlobalCardinalityConstraint::new(
+        color.clone(),
+        card,
+    )));
+    solver.add_objective(Box::new(ColorCount {
+        color: color.clone(),
+    }));
+    if !solver.solve() {
+        println!("no coloring");
+        return;
+    }
+    println!("{}", solver.get_objective());
+    for (i, var) in color.iter().enumerate() {
+        println!("{} {}", i, var.borrow().value());
+    }
+}
@@ -0,0 +1,57 @@
+/* This program lists pairs of integers satisfying a conditional constraint.
+ *
+ * Input format:
+ * N
+ *
+ * Output format:
+ * every pair (x, y) with 0 <= x, y <= N such that
+ * x + y = N if x <= y, and x - y = 1 otherwise
+ */
+use ezcp::arithmetic::SimpleArithmeticConstraint;
+use ezcp::reify::{reify_le, IfThenElseConstraint};
+use ezcp::solver::Solver;
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable_selector::FirstFailVariableSelector;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+
+fn read_int() -> i64 {
+    let mut input_line = String::new();
+    io::stdin().read_line(&mut input_line).expect("No input!");
+    input_line
+        .trim()
+        .parse()
+        .expect("Input is not a valid integer!")
+}
+
+fn main() {
+    let n = read_int();
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, n, "x".to_string());
+    let y = solver.new_variable(0, n, "y".to_string());
+    let le = reify_le(&mut solver, x.clone(), y.clone());
+    let sum = Rc::new(RefCell::new(SimpleArithmeticConstraint::new(
+        x.clone(),
+        y.clone(),
+        n,
+        true,
+    )));
+    let difference = Rc::new(RefCell::new(SimpleArithmeticConstraint::new(
+        x.clone(),
+        y.clone(),
+        1,
+        false,
+    )));
+    solver.add_constraint(Box::new(IfThenElseConstraint::new(le, sum, difference)));
+    // x and y are the first variables created
+    for solution in solver.solve_all() {
+        let (x, y) = (solution[0], solution[1]);
+        assert!(if x <= y { x + y == n } else { x - y == 1 });
+        println!("{} {}", x, y);
+    }
+}
@@ -28,26 +28,48 @@ struct Scanner {
     buffer: Vec<String>
 }
 
+fn fail(msg: String) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
 impl Scanner {
-    fn next<T: std::str::FromStr>(&mut self) -> T {
+    fn next<T: std::str::FromStr>(&mut self, what: &str) -> T {
         loop {
             if let Some(token) = self.buffer.pop() {
-                return token.parse().ok().expect("Failed parse");
+                return token
+                    .parse()
+                    .unwrap_or_else(|_| fail(format!("Invalid {}: {}", what, token)));
             }
             let mut input = String::new();
-            std::io::stdin().read_line(&mut input).expect("Failed read");
+            match std::io::stdin().read_line(&mut input) {
+                Ok(0) => fail(format!("Unexpected end of input, expected {}", what)),
+                Ok(_) => {}
+                Err(e) => fail(format!("Failed to read input: {}", e)),
+            }
             self.buffer = input.split_whitespace().rev().map(String::from).collect();
         }
     }
 }
 
 fn read_graph(scanner: &mut Scanner) -> Vec<Vec<usize>> {
-    let vertices = scanner.next::<usize>();
-    let edges = scanner.next::<usize>();
+    let vertices = scanner.next::<usize>("number of vertices");
+    if vertices == 0 {
+        fail("Graph has no vertices".to_string());
+    }
+    let edges = scanner.next::<usize>("number of edges");
     let mut graph = vec![Vec::new(); vertices];
     for _ in 0..edges {
-        let v = scanner.next::<usize>();
-        let u = scanner.next::<usize>();
+        let v = scanner.next::<usize>("edge endpoint");
+        let u = scanner.next::<usize>("edge endpoint");
+        if v >= vertices || u >= vertices {
+            fail(format!(
+                "Edge ({}, {}) is out of range, vertices are 0..{}",
+                v,
+                u,
+                vertices - 1
+            ));
+        }
         graph[v].push(u);
         graph[u].push(v);
     }
@@ -92,6 +114,10 @@ impl Constraint for DegreeConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.parent.clone()
+    }
 }
 
 struct DegreePropagator {
@@ -138,7 +164,7 @@ impl Propagator for DegreePropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
 }
@@ -147,7 +173,7 @@ fn main() {
     let mut scanner = Scanner::default();
     let g = read_graph(&mut scanner);
     let n = g.len();
-    let max_degree = scanner.next::<usize>();
+    let max_degree = scanner.next::<usize>("maximum degree");
     let mut solver = Solver::new(
         Box::new(FirstFailVariableSelector {}),
         Box::new(MinValueSelector {}),
@@ -158,15 +184,14 @@ fn main() {
         parent.push(solver.new_variable(0, (n as i64) - 1, format!("parent_{}", i)));
     }
     for v in 0..n {
-        let mut ptr = 0;
+        let mut adjacent = vec![false; n];
         for u in g[v].iter().cloned() {
-            while ptr < u {
-                if ptr != v {
-                    parent[v].borrow_mut().remove(ptr as i64);
-                }
-                ptr += 1;
+            adjacent[u] = true;
+        }
+        for (u, adj) in adjacent.iter().enumerate() {
+            if u != v && !adj {
+                parent[v].borrow_mut().remove(u as i64);
             }
-            ptr += 1;
         }
     }
     solver.add_constraint(Box::new(TreeConstraint::new(
@@ -0,0 +1,160 @@
+/* This program solves job-shop scheduling problem:
+ * every job is a sequence of operations, each operation runs on some machine
+ * for a fixed time, operations of a job run in order, every machine runs
+ * at most one operation at a time, and the makespan (the time all jobs are done) is minimal.
+ *
+ * Single command line argument:
+ * path to file with the following format:
+ * n m (number of jobs and machines)
+ * n lines, one per job, with pairs "machine duration" for its operations in order, 0 <= machine < m
+ *
+ * Use sample.txt for example.
+ *
+ * Output format:
+ * makespan
+ * start times of operations of each job, one job per line
+ */
+use ezcp::linear::LinearInequalityConstraint;
+use ezcp::objective_function::ObjectiveFunction;
+use ezcp::scheduling::CumulativeConstraint;
+use ezcp::solver::Solver;
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::Variable;
+use ezcp::variable_selector::FirstFailVariableSelector;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::rc::Rc;
+
+// time the last operation of every job is done
+struct Makespan {
+    last: Vec<(Rc<RefCell<Variable>>, i64)>,
+}
+
+impl ObjectiveFunction for Makespan {
+    fn eval(&self) -> i64 {
+        self.last
+            .iter()
+            .map(|(s, d)| s.borrow().value() + d)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn bound(&self) -> i64 {
+        self.last
+            .iter()
+            .map(|(s, d)| s.borrow().get_lb() + d)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn fail(msg: String) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+fn parse_numbers(line: &str) -> Vec<i64> {
+    line.split_whitespace()
+        .map(|x| {
+            x.parse::<i64>()
+                .unwrap_or_else(|_| fail(format!("Invalid number: {}", x)))
+        })
+        .collect()
+}
+
+// jobs as lists of (machine, duration)
+fn read_instance(filename: &str) -> (usize, Vec<Vec<(usize, i64)>>) {
+    let file = File::open(filename).unwrap_or_else(|e| fail(format!("Cannot open {}: {}", filename, e)));
+    let reader = BufReader::new(file);
+    let mut lines = reader
+        .lines()
+        .map(|l| l.unwrap_or_else(|e| fail(format!("Cannot read {}: {}", filename, e))))
+        .filter(|l| !l.trim().is_empty());
+    let header = parse_numbers(&lines.next().unwrap_or_else(|| fail("Empty file".to_string())));
+    if header.len() != 2 || header.iter().any(|x| *x < 0) {
+        fail("Header has to be \"n m\"".to_string());
+    }
+    let (n, m) = (header[0] as usize, header[1] as usize);
+    let mut jobs = Vec::with_capacity(n);
+    for i in 0..n {
+        let line = lines
+            .next()
+            .unwrap_or_else(|| fail(format!("Unexpected end of file, expected job {}", i)));
+        let numbers = parse_numbers(&line);
+        if !numbers.len().is_multiple_of(2) {
+            fail(format!("Job {} has a machine without duration", i));
+        }
+        let mut job = Vec::with_capacity(numbers.len() / 2);
+        for op in numbers.chunks(2) {
+            if op[0] < 0 || op[0] as usize >= m || op[1] < 0 {
+                fail(format!("Invalid operation \"{} {}\" in job {}", op[0], op[1], i));
+            }
+            job.push((op[0] as usize, op[1]));
+        }
+        jobs.push(job);
+    }
+    (m, jobs)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        fail(format!("Usage: {} <instance>", args[0]));
+    }
+    let (m, jobs) = read_instance(&args[1]);
+    // running everything one after another is always feasible
+    let horizon: i64 = jobs.iter().flatten().map(|(_, d)| d).sum();
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let mut starts = Vec::with_capacity(jobs.len());
+    // start times and durations of operations on each machine
+    let mut machines = vec![(Vec::new(), Vec::new()); m];
+    for (i, job) in jobs.iter().enumerate() {
+        let mut job_starts: Vec<Rc<RefCell<Variable>>> = Vec::with_capacity(job.len());
+        for (k, (machine, duration)) in job.iter().cloned().enumerate() {
+            let s = solver.new_variable(0, horizon - duration, format!("start_{}_{}", i, k));
+            if let Some(prev) = job_starts.last() {
+                // prev + duration of prev <= s
+                solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+                    vec![prev.clone(), s.clone()],
+                    vec![1, -1],
+                    -job[k - 1].1,
+                )));
+            }
+            machines[machine].0.push(s.clone());
+            machines[machine].1.push(duration);
+            job_starts.push(s);
+        }
+        starts.push(job_starts);
+    }
+    for (machine_starts, durations) in machines {
+        let demands = vec![1; durations.len()];
+        solver.add_constraint(Box::new(CumulativeConstraint::new(
+            machine_starts,
+            durations,
+            demands,
+            1,
+        )));
+    }
+    let last = starts
+        .iter()
+        .zip(jobs.iter())
+        .filter_map(|(s, job)| Some((s.last()?.clone(), job.last()?.1)))
+        .collect();
+    solver.add_objective(Box::new(Makespan { last }));
+    if !solver.solve() {
+        fail("No schedule found".to_string());
+    }
+    println!("{}", solver.get_objective());
+    for job_starts in starts {
+        let values: Vec<String> = job_starts
+            .iter()
+            .map(|s| s.borrow().value().to_string())
+            .collect();
+        println!("{}", values.join(" "));
+    }
+}
@@ -8,18 +8,27 @@
 use ezcp::logic::{AndConstraint, NegateConstraint, OrConstraint};
 use ezcp::solver::Solver;
 use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::BoolVar;
 use ezcp::variable_selector::FirstFailVariableSelector;
 use std::boxed::Box;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+fn fail(msg: String) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+// returns number of variables and clauses, literal (i, true) stands for not x_i
 fn read_cnf_file(filename: &str) -> (usize, Vec<Vec<(usize, bool)>>) {
-    let file = File::open(filename).unwrap();
+    let file = File::open(filename).unwrap_or_else(|e| fail(format!("Cannot open {}: {}", filename, e)));
     let reader = BufReader::new(file);
     let mut n_vars = 0;
     let mut n_clauses = 0;
     let mut clauses = vec![Vec::new(); 1];
-    for line in reader.lines().map(|l| l.unwrap()) {
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|e| fail(format!("Cannot read {}: {}", filename, e)));
+        let line = line.trim();
         if line.is_empty() {
             continue;
         }
@@ -28,37 +37,63 @@ fn read_cnf_file(filename: &str) -> (usize, Vec<Vec<(usize, bool)>>) {
             continue;
         }
         if begin == 'p' {
-            let tokens: Vec<_> = line.split(' ').filter(|x| !x.is_empty()).collect();
-            assert!(tokens.len() == 4);
+            let tokens: Vec<_> = line.split_whitespace().collect();
+            if tokens.len() != 4 {
+                fail(format!("Malformed DIMACS header on line {}: {}", line_no + 1, line));
+            }
             if tokens[1] != "cnf" {
-                panic!("Unsupported format: {}", tokens[1]);
+                fail(format!("Unsupported format: {}", tokens[1]));
             }
-            n_vars = tokens[2].parse::<usize>().unwrap();
-            n_clauses = tokens[3].parse::<usize>().unwrap();
+            n_vars = tokens[2]
+                .parse::<usize>()
+                .unwrap_or_else(|_| fail(format!("Invalid number of variables: {}", tokens[2])));
+            n_clauses = tokens[3]
+                .parse::<usize>()
+                .unwrap_or_else(|_| fail(format!("Invalid number of clauses: {}", tokens[3])));
             continue;
         }
-        if !begin.is_ascii_digit() && begin != ' ' && begin != '-' {
+        if !begin.is_ascii_digit() && begin != '-' {
             continue;
         }
-        for literal in line.split(' ').filter(|x| !x.is_empty()).map(|x| x.parse::<i32>().unwrap()) {
+        for token in line.split_whitespace() {
+            let literal = token.parse::<i64>().unwrap_or_else(|_| {
+                fail(format!("Invalid literal on line {}: {}", line_no + 1, token))
+            });
+            if literal.unsigned_abs() as usize > n_vars {
+                fail(format!(
+                    "Literal {} on line {} is out of range, header declares {} variables",
+                    literal,
+                    line_no + 1,
+                    n_vars
+                ));
+            }
             if literal == 0 {
                 clauses.push(Vec::new());
             } else {
                 if literal < 0 {
-                    clauses.last_mut().unwrap().push(((-literal - 1) as usize, false));
+                    clauses.last_mut().unwrap().push(((-literal - 1) as usize, true));
                 } else {
-                    clauses.last_mut().unwrap().push(((literal - 1) as usize, true));
+                    clauses.last_mut().unwrap().push(((literal - 1) as usize, false));
                 }
             }
         }
     }
     clauses = clauses.iter().filter(|x| !x.is_empty()).cloned().collect();
-    assert!(clauses.len() == n_clauses);
+    if clauses.len() != n_clauses {
+        eprintln!(
+            "Warning: header declares {} clauses, but file contains {}",
+            n_clauses,
+            clauses.len()
+        );
+    }
     (n_vars, clauses)
 }
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        fail(format!("Usage: {} <file.cnf>", args[0]));
+    }
     let (n_vars, clauses) = read_cnf_file(&args[1]);
     let mut solver = Solver::new(
         Box::new(FirstFailVariableSelector {}),
@@ -68,14 +103,14 @@ fn main() {
     let mut negations = Vec::with_capacity(n_vars);
     let mut clause_vars = Vec::with_capacity(clauses.len());
     for i in 0..n_vars {
-        let v = solver.new_variable(0, 1, format!("v_{}", i));
-        let nv = solver.new_variable(0, 1, format!("not v_{}", i));
+        let v = solver.new_bool(format!("v_{}", i));
+        let nv = solver.new_bool(format!("not v_{}", i));
         vars.push(v.clone());
         negations.push(nv.clone());
-        solver.add_constraint(Box::new(NegateConstraint::new(v, nv)));
+        solver.add_constraint(Box::new(NegateConstraint::from_bools(v, nv)));
     }
     for i in 0..clauses.len() {
-        let cv = solver.new_variable(0, 1, format!("clause_{}", i));
+        let cv = solver.new_bool(format!("clause_{}", i));
         clause_vars.push(cv.clone());
         let mut v = Vec::new();
         for (id, neg) in clauses[i].iter().cloned() {
@@ -85,16 +120,16 @@ fn main() {
                 v.push(vars[id].clone());
             }
         }
-        solver.add_constraint(Box::new(OrConstraint::new(cv, v)));
+        solver.add_constraint(Box::new(OrConstraint::from_bools(cv, v)));
     }
-    let sat_var = solver.new_variable(1, 1, format!("sat"));
-    solver.add_constraint(Box::new(AndConstraint::new(sat_var.clone(), clause_vars.clone())));
+    let sat_var = BoolVar::new(solver.new_variable(1, 1, format!("sat")));
+    solver.add_constraint(Box::new(AndConstraint::from_bools(sat_var, clause_vars)));
     if !solver.solve() {
         println!("Unsatisfiable.");
     } else {
         println!("Satisfiable.");
         for v in &vars {
-            print!("{} ", v.borrow().value());
+            print!("{} ", if v.is_true() { 1 } else { 0 });
         }
         println!("");
     }
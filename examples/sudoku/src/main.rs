@@ -4,6 +4,7 @@
  * but it is sufficient for demonstrating CP solution.
  */
 use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::constraint::Constraint;
 use ezcp::solver::Solver;
 use ezcp::value_selector::MinValueSelector;
 use ezcp::variable_selector::FirstFailVariableSelector;
@@ -136,19 +137,20 @@ fn main() {
             }
         }
     }
+    let mut constraints = Vec::<Box<dyn Constraint>>::with_capacity(27);
     for i in 0..9 {
         let mut v = Vec::with_capacity(9);
         for j in 0..9 {
             v.push(vars[i * 9 + j].clone());
         }
-        solver.add_constraint(Box::new(AllDifferentConstraint::new(v)));
+        constraints.push(Box::new(AllDifferentConstraint::new(v)));
     }
     for j in 0..9 {
         let mut v = Vec::with_capacity(9);
         for i in 0..9 {
             v.push(vars[i * 9 + j].clone());
         }
-        solver.add_constraint(Box::new(AllDifferentConstraint::new(v)));
+        constraints.push(Box::new(AllDifferentConstraint::new(v)));
     }
     for i in 0..3 {
         for j in 0..3 {
@@ -158,9 +160,10 @@ fn main() {
                     v.push(vars[(i * 3 + di) * 9 + j * 3 + dj].clone());
                 }
             }
-            solver.add_constraint(Box::new(AllDifferentConstraint::new(v)));
+            constraints.push(Box::new(AllDifferentConstraint::new(v)));
         }
     }
+    solver.add_constraints(constraints);
     assert!(solver.solve());
     println!("Solver found solution:");
     for i in 0..9 {
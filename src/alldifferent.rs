@@ -1,6 +1,7 @@
 use crate::constraint::Constraint;
 use crate::events::Event;
-use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::propagator::{max_domain_size, Propagator, PropagatorControlBlock, PRIORITY_LOW};
+use crate::reify::ReifiedEqualityConstraint;
 use crate::solver::Solver;
 use crate::variable::Variable;
 use std::cell::RefCell;
@@ -15,31 +16,57 @@ impl AllDifferentConstraint {
     pub fn new(vars: Vec<Rc<RefCell<Variable>>>) -> Self {
         Self { vars }
     }
+    /// posts the pairwise decomposition of this constraint instead of using the matching propagator,
+    /// returns a boolean for each pair i < j, equal to 1 iff vars[i] = vars[j]
+    /// the returned booleans count violations, fixing all of them to 0 is equivalent to the constraint
+    pub fn decompose_with_violations(&self, solver: &mut Solver) -> Vec<Rc<RefCell<Variable>>> {
+        let mut violations = Vec::new();
+        for i in 0..self.vars.len() {
+            for j in i + 1..self.vars.len() {
+                let name = format!(
+                    "{} == {}",
+                    self.vars[i].borrow().name,
+                    self.vars[j].borrow().name
+                );
+                let b = solver.new_variable(0, 1, name);
+                solver.add_constraint(Box::new(ReifiedEqualityConstraint::new(
+                    self.vars[i].clone(),
+                    self.vars[j].clone(),
+                    b.clone(),
+                )));
+                violations.push(b);
+            }
+        }
+        violations
+    }
 }
 
 impl Constraint for AllDifferentConstraint {
     fn satisfied(&self) -> bool {
         let mut vals = HashSet::new();
         for v in &self.vars {
-            if v.borrow().is_assigned() {
-                let val = v.borrow().value();
-                if !vals.insert(val) {
-                    return false;
-                }
-            } else {
+            let Some(val) = v.borrow().try_value() else {
+                return false;
+            };
+            if !vals.insert(val) {
                 return false;
             }
         }
         true
     }
     fn create_propagators(&self, solver: &mut Solver) {
-        let p = Rc::new(RefCell::new(AllDifferentACPropagator::new(
+        let max_vertices = solver.get_config().alldifferent_ac_max_vertices;
+        let p = Rc::new(RefCell::new(AllDifferentACPropagator::with_vertex_limit(
             self.vars.clone(),
+            max_vertices,
             solver.new_propagator_id(),
         )));
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
 }
 
 pub struct SCC {
@@ -261,8 +288,9 @@ impl ACMatching {
         }
         for i in n..vals.len() + n {
             let e = edges.len();
-            if count.is_some() {
-                edges.push(FlowEdge::new(t, *count.unwrap().get(&vals[i - n]).unwrap()));
+            if let Some(count) = count {
+                // values without a count can not be taken
+                edges.push(FlowEdge::new(t, count.get(&vals[i - n]).cloned().unwrap_or(0)));
             } else {
                 edges.push(FlowEdge::new(t, 1));
             }
@@ -393,15 +421,269 @@ impl ACMatching {
 pub struct AllDifferentACPropagator {
     pcb: PropagatorControlBlock,
     vars: Vec<Rc<RefCell<Variable>>>,
+    max_vertices: Option<usize>,
+    // matching of the last call, the next one is repaired from it instead of being built from scratch
+    // it is checked against the domains, so it needs no rollback on backtracking
+    mate: Vec<Option<i64>>,
+    // variable matched to each value, inverse of mate
+    owner: HashMap<i64, usize>,
+    augmentations: usize,
 }
 
 impl AllDifferentACPropagator {
     pub fn new(vars: Vec<Rc<RefCell<Variable>>>, id: usize) -> Self {
+        Self::with_vertex_limit(vars, None, id)
+    }
+
+    /// falls back to bounds consistency when the value graph could have more than max_vertices vertices
+    pub fn with_vertex_limit(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        max_vertices: Option<usize>,
+        id: usize,
+    ) -> Self {
+        let mate = vec![None; vars.len()];
         Self {
             pcb: PropagatorControlBlock::new(id),
             vars,
+            max_vertices,
+            mate,
+            owner: HashMap::new(),
+            augmentations: 0,
         }
     }
+
+    /// augmenting paths searched by all calls to propagate so far
+    pub fn augmentations(&self) -> usize {
+        self.augmentations
+    }
+
+    // augmenting path from unmatched variable i, values on visited paths are not tried again
+    fn augment(&mut self, i: usize, visited: &mut HashSet<i64>) -> bool {
+        let values: Vec<i64> = self.vars[i].borrow().iter().collect();
+        let mut found = values.iter().find(|val| !self.owner.contains_key(val)).cloned();
+        if found.is_none() {
+            for val in values {
+                if visited.insert(val) && self.augment(self.owner[&val], visited) {
+                    found = Some(val);
+                    break;
+                }
+            }
+        }
+        let Some(val) = found else {
+            return false;
+        };
+        self.mate[i] = Some(val);
+        self.owner.insert(val, i);
+        true
+    }
+
+    // drops pairs which are no longer possible and matches the variables left without a value again
+    fn repair_matching(&mut self) -> bool {
+        for (i, v) in self.vars.iter().enumerate() {
+            if let Some(val) = self.mate[i] {
+                if !v.borrow().possible(val) {
+                    self.mate[i] = None;
+                    self.owner.remove(&val);
+                }
+            }
+        }
+        let mut visited = HashSet::new();
+        for i in 0..self.vars.len() {
+            if self.mate[i].is_none() {
+                visited.clear();
+                self.augmentations += 1;
+                if !self.augment(i, &mut visited) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn too_large(&self) -> bool {
+        let Some(limit) = self.max_vertices else {
+            return false;
+        };
+        let lb = self.vars.iter().map(|v| v.borrow().get_lb()).min();
+        let ub = self.vars.iter().map(|v| v.borrow().get_ub()).max();
+        match (lb, ub) {
+            (Some(lb), Some(ub)) => {
+                (self.vars.len() as i128) + (ub as i128) - (lb as i128) + 1 > limit as i128
+            }
+            _ => false,
+        }
+    }
+
+    // Hall intervals: if the domains of k variables lie in an interval of k values,
+    // other variables can not take values from it, repeated until nothing changes
+    fn propagate_bounds(&mut self) {
+        if self.vars.is_empty() {
+            return;
+        }
+        loop {
+            let mut lb: Vec<i128> = self
+                .vars
+                .iter()
+                .map(|v| v.borrow().get_lb() as i128)
+                .collect();
+            let mut ub: Vec<i128> = self
+                .vars
+                .iter()
+                .map(|v| v.borrow().get_ub() as i128)
+                .collect();
+            if !hall_interval_bounds(&mut lb, &mut ub) {
+                self.vars[0].borrow().fail();
+                return;
+            }
+            // bounds may move further when they land on holes, so the pass is repeated
+            let mut changed = false;
+            for (i, v) in self.vars.iter().enumerate() {
+                let mut v = v.borrow_mut();
+                let old = (v.get_lb(), v.get_ub());
+                if !v.tighten(lb[i] as i64, ub[i] as i64) {
+                    return;
+                }
+                changed |= old != (v.get_lb(), v.get_ub());
+            }
+            if !changed {
+                return;
+            }
+        }
+    }
+}
+
+// last element of the path from x in the forest given by parent links t, following increasing links
+fn path_max(t: &[usize], mut x: usize) -> usize {
+    while t[x] > x {
+        x = t[x];
+    }
+    x
+}
+
+// same following decreasing links
+fn path_min(t: &[usize], mut x: usize) -> usize {
+    while t[x] < x {
+        x = t[x];
+    }
+    x
+}
+
+// links every element on the path from start to end to to
+fn path_set(t: &mut [usize], start: usize, end: usize, to: usize) {
+    let mut x = start;
+    while x != end {
+        let next = t[x];
+        t[x] = to;
+        x = next;
+    }
+}
+
+// removes Hall intervals from the bounds [lb[i], ub[i]] of an alldifferent, in O(n log n)
+// this is the algorithm of Lopez-Ortiz, Quimper, Tromp and van Beek,
+// "A fast and simple algorithm for bounds consistency of the alldifferent constraint"
+// returns false if some interval of values is shared by more variables than it has values
+fn hall_interval_bounds(lb: &mut [i128], ub: &mut [i128]) -> bool {
+    let n = lb.len();
+    let mut minsorted: Vec<usize> = (0..n).collect();
+    minsorted.sort_by_key(|&i| lb[i]);
+    let mut maxsorted: Vec<usize> = (0..n).collect();
+    maxsorted.sort_by_key(|&i| ub[i]);
+    // distinct values of lb[i] and ub[i] + 1 in increasing order between two sentinels,
+    // consecutive ones delimit the intervals of values the variables are counted against
+    let mut bounds = Vec::with_capacity(2 * n + 2);
+    let mut minrank = vec![0; n];
+    let mut maxrank = vec![0; n];
+    let mut last = lb[minsorted[0]] - 2;
+    bounds.push(last);
+    let (mut i, mut j) = (0, 0);
+    while j < n {
+        if i < n && lb[minsorted[i]] <= ub[maxsorted[j]] + 1 {
+            if lb[minsorted[i]] != last {
+                last = lb[minsorted[i]];
+                bounds.push(last);
+            }
+            minrank[minsorted[i]] = bounds.len() - 1;
+            i += 1;
+        } else {
+            if ub[maxsorted[j]] + 1 != last {
+                last = ub[maxsorted[j]] + 1;
+                bounds.push(last);
+            }
+            maxrank[maxsorted[j]] = bounds.len() - 1;
+            j += 1;
+        }
+    }
+    let nb = bounds.len() - 1;
+    bounds.push(bounds[nb] + 2);
+    // t links intervals to the next one with free capacity d, h links them across Hall intervals
+    let mut t = vec![0; nb + 2];
+    let mut h = vec![0; nb + 2];
+    let mut d = vec![0; nb + 2];
+
+    // lower bounds, variables by increasing upper bound
+    for k in 1..=nb + 1 {
+        t[k] = k - 1;
+        h[k] = k - 1;
+        d[k] = bounds[k] - bounds[k - 1];
+    }
+    for &v in &maxsorted {
+        let (x, y) = (minrank[v], maxrank[v]);
+        let mut z = path_max(&t, x + 1);
+        let j = t[z];
+        d[z] -= 1;
+        if d[z] == 0 {
+            t[z] = z + 1;
+            z = path_max(&t, t[z]);
+            t[z] = j;
+        }
+        path_set(&mut t, x + 1, z, z);
+        if d[z] < bounds[z] - bounds[y] {
+            return false;
+        }
+        if h[x] > x {
+            let w = path_max(&h, h[x]);
+            lb[v] = bounds[w];
+            path_set(&mut h, x, w, w);
+        }
+        if d[z] == bounds[z] - bounds[y] {
+            let start = h[y];
+            path_set(&mut h, start, j - 1, y);
+            h[y] = j - 1;
+        }
+    }
+
+    // upper bounds, symmetrically, variables by decreasing lower bound
+    for k in 0..=nb {
+        t[k] = k + 1;
+        h[k] = k + 1;
+        d[k] = bounds[k + 1] - bounds[k];
+    }
+    for &v in minsorted.iter().rev() {
+        let (x, y) = (maxrank[v], minrank[v]);
+        let mut z = path_min(&t, x - 1);
+        let j = t[z];
+        d[z] -= 1;
+        if d[z] == 0 {
+            t[z] = z - 1;
+            z = path_min(&t, t[z]);
+            t[z] = j;
+        }
+        path_set(&mut t, x - 1, z, z);
+        if d[z] < bounds[y] - bounds[z] {
+            return false;
+        }
+        if h[x] < x {
+            let w = path_min(&h, h[x]);
+            ub[v] = bounds[w] - 1;
+            path_set(&mut h, x, w, w);
+        }
+        if d[z] == bounds[y] - bounds[z] {
+            let start = h[y];
+            path_set(&mut h, start, j + 1, y);
+            h[y] = j + 1;
+        }
+    }
+    true
 }
 
 impl Propagator for AllDifferentACPropagator {
@@ -413,18 +695,38 @@ impl Propagator for AllDifferentACPropagator {
     }
 
     fn propagate(&mut self) {
-        let mut m = ACMatching::new(&self.vars, None);
-        if let Some(g) = m.matching(MatchingReturnValue::MatchingGraph) {
-            let mut scc = SCC::new(g);
-            let mut edges = scc.get_bad_edges();
-            for (val, i) in edges.drain(..) {
-                println!("removed {} from {}", m.vals[val - self.vars.len()], &self.vars[i].borrow().name);
-                self.vars[i]
-                    .borrow_mut()
-                    .remove(m.vals[val - self.vars.len()]);
-            }
-        } else {
+        if self.too_large() {
+            self.propagate_bounds();
+            return;
+        }
+        if !self.repair_matching() {
             self.vars[0].borrow().fail();
+            return;
+        }
+        // value graph with matched edges from variables to values and the others back,
+        // values get vertices n, n + 1, ... in the order they are met
+        let n = self.vars.len();
+        let mut vals = Vec::<i64>::new();
+        let mut vertex = HashMap::<i64, usize>::new();
+        let mut g = vec![Vec::<usize>::new(); n];
+        for (i, v) in self.vars.iter().enumerate() {
+            for val in v.borrow().iter() {
+                let u = *vertex.entry(val).or_insert_with(|| {
+                    vals.push(val);
+                    g.push(Vec::new());
+                    n + vals.len() - 1
+                });
+                if self.mate[i] == Some(val) {
+                    g[i].push(u);
+                } else {
+                    g[u].push(i);
+                }
+            }
+        }
+        let mut scc = SCC::new(g);
+        let mut edges = scc.get_bad_edges();
+        for (val, i) in edges.drain(..) {
+            self.vars[i].borrow_mut().remove(vals[val - n]);
         }
     }
 
@@ -436,7 +738,141 @@ impl Propagator for AllDifferentACPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * max_domain_size(&self.vars)
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_LOW
+    }
+}
+
+/// vars[i] are pairwise distinct among those with present[i] = 1
+/// only variables known to be present get a vertex in the value graph of the matching,
+/// absent and undecided ones take no value from the others
+/// an undecided variable becomes absent once every value in its domain is taken by a present assigned variable
+pub struct AllDifferentIfConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    present: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl AllDifferentIfConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, present: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert!(vars.len() == present.len());
+        Self { vars, present }
+    }
+}
+
+impl Constraint for AllDifferentIfConstraint {
+    fn satisfied(&self) -> bool {
+        let mut vals = HashSet::new();
+        for (v, p) in self.vars.iter().zip(self.present.iter()) {
+            match p.borrow().try_value() {
+                Some(0) => {}
+                Some(_) => match v.borrow().try_value() {
+                    Some(val) => {
+                        if !vals.insert(val) {
+                            return false;
+                        }
+                    }
+                    None => {
+                        return false;
+                    }
+                },
+                None => {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(AllDifferentIfPropagator::new(
+            self.vars.clone(),
+            self.present.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.iter().chain(self.present.iter()).cloned().collect()
+    }
+}
+
+pub struct AllDifferentIfPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    present: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl AllDifferentIfPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        present: Vec<Rc<RefCell<Variable>>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            present,
+        }
+    }
+}
+
+impl Propagator for AllDifferentIfPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in self.vars.iter().chain(self.present.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let mut present_vars = Vec::new();
+        for (v, p) in self.vars.iter().zip(self.present.iter()) {
+            if !p.borrow_mut().tighten(0, 1) {
+                return;
+            }
+            if p.borrow().try_value() == Some(1) {
+                present_vars.push(v.clone());
+            }
+        }
+        if present_vars.len() > 1 {
+            AllDifferentACPropagator::new(present_vars.clone(), self.pcb.id).propagate();
+        }
+        let taken: HashSet<i64> = present_vars
+            .iter()
+            .filter_map(|v| v.borrow().try_value())
+            .collect();
+        for (v, p) in self.vars.iter().zip(self.present.iter()) {
+            if p.borrow().is_assigned() {
+                continue;
+            }
+            let blocked = v.borrow().iter().all(|val| taken.contains(&val));
+            if blocked && !p.borrow_mut().remove(1) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * max_domain_size(&self.vars)
+    }
 }
@@ -1,6 +1,9 @@
 use crate::constraint::Constraint;
 use crate::events::Event;
-use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::linear::{ceil_div, floor_div};
+use crate::propagator::{
+    Explanation, Propagator, PropagatorControlBlock, Reason, PRIORITY_HIGH,
+};
 use crate::solver::Solver;
 use crate::variable::Variable;
 use std::cell::RefCell;
@@ -22,14 +25,13 @@ impl SimpleArithmeticConstraint {
 
 impl Constraint for SimpleArithmeticConstraint {
     fn satisfied(&self) -> bool {
-        if !self.x.borrow().is_assigned() || !self.y.borrow().is_assigned() {
-            false
+        let (Some(x), Some(y)) = (self.x.borrow().try_value(), self.y.borrow().try_value()) else {
+            return false;
+        };
+        if self.plus {
+            x + y == self.c
         } else {
-            if self.plus {
-                self.x.borrow().value() + self.y.borrow().value() == self.c
-            } else {
-                self.x.borrow().value() - self.y.borrow().value() == self.c
-            }
+            x - y == self.c
         }
     }
 
@@ -44,6 +46,10 @@ impl Constraint for SimpleArithmeticConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
 }
 
 pub struct SimpleArithmeticPropagator {
@@ -203,6 +209,19 @@ impl Propagator for SimpleArithmeticPropagator {
         }
     }
 
+    fn explain(&self, var: Rc<RefCell<Variable>>, val: i64) -> Explanation {
+        // the only support of val is missing from the other variable
+        if Rc::ptr_eq(&var, &self.x) {
+            let support = if self.plus { self.c - val } else { val - self.c };
+            vec![(self.y.clone(), support, Reason::Removed)]
+        } else if Rc::ptr_eq(&var, &self.y) {
+            let support = if self.plus { self.c - val } else { val + self.c };
+            vec![(self.x.clone(), support, Reason::Removed)]
+        } else {
+            vec![(var, val, Reason::Decision)]
+        }
+    }
+
     fn get_cb(&self) -> &PropagatorControlBlock {
         &self.pcb
     }
@@ -211,7 +230,683 @@ impl Propagator for SimpleArithmeticPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
 }
+
+/// cost = sum |vars[i] - target|
+pub struct SumAbsDeviationConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    target: i64,
+    cost: Rc<RefCell<Variable>>,
+}
+
+impl SumAbsDeviationConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, target: i64, cost: Rc<RefCell<Variable>>) -> Self {
+        Self { vars, target, cost }
+    }
+}
+
+impl Constraint for SumAbsDeviationConstraint {
+    fn satisfied(&self) -> bool {
+        let Some(cost) = self.cost.borrow().try_value() else {
+            return false;
+        };
+        let mut sum = 0;
+        for v in &self.vars {
+            let Some(x) = v.borrow().try_value() else {
+                return false;
+            };
+            sum += (x - self.target).abs();
+        }
+        sum == cost
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(SumAbsDeviationPropagator::new(
+            self.vars.clone(),
+            self.target,
+            self.cost.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.vars.clone();
+        vars.push(self.cost.clone());
+        vars
+    }
+}
+
+pub struct SumAbsDeviationPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    target: i64,
+    cost: Rc<RefCell<Variable>>,
+}
+
+impl SumAbsDeviationPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        target: i64,
+        cost: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            target,
+            cost,
+        }
+    }
+
+    // smallest and largest deviation of a single variable based on its bounds
+    fn deviation_bounds(&self, var: &Variable) -> (i64, i64) {
+        let lb = var.get_lb();
+        let ub = var.get_ub();
+        let min_dev = if lb > self.target {
+            lb - self.target
+        } else if ub < self.target {
+            self.target - ub
+        } else {
+            0
+        };
+        let max_dev = i64::max((lb - self.target).abs(), (ub - self.target).abs());
+        (min_dev, max_dev)
+    }
+}
+
+impl Propagator for SumAbsDeviationPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+        self.cost
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::LowerBound);
+        self.cost
+            .borrow_mut()
+            .add_listener(self_pointer, Event::UpperBound);
+    }
+
+    fn propagate(&mut self) {
+        let mut dev = Vec::with_capacity(self.vars.len());
+        let mut min_sum = 0;
+        let mut max_sum = 0;
+        for v in &self.vars {
+            let (min_dev, max_dev) = self.deviation_bounds(&v.borrow());
+            min_sum += min_dev;
+            max_sum += max_dev;
+            dev.push((min_dev, max_dev));
+        }
+        {
+            let mut cost = self.cost.borrow_mut();
+            if !cost.tighten(min_sum, max_sum) {
+                return;
+            }
+        }
+        let cost_lb = self.cost.borrow().get_lb();
+        let cost_ub = self.cost.borrow().get_ub();
+        for (i, v) in self.vars.iter().enumerate() {
+            let mut var = v.borrow_mut();
+            // every other variable deviates at least min_sum - dev[i].0 in total
+            let up = cost_ub - min_sum + dev[i].0;
+            if !var.tighten(self.target - up, self.target + up) {
+                return;
+            }
+            // every other variable deviates at most max_sum - dev[i].1 in total
+            let down = cost_lb - max_sum + dev[i].1;
+            if down > 0 {
+                if var.get_lb() > self.target - down && !var.set_lb(self.target + down) {
+                    return;
+                }
+                if var.get_ub() < self.target + down && !var.set_ub(self.target - down) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
+
+/// result = min(xs)
+pub struct MinimumConstraint {
+    result: Rc<RefCell<Variable>>,
+    xs: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl MinimumConstraint {
+    pub fn new(result: Rc<RefCell<Variable>>, xs: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert!(!xs.is_empty());
+        Self { result, xs }
+    }
+}
+
+/// result = max(xs)
+pub struct MaximumConstraint {
+    result: Rc<RefCell<Variable>>,
+    xs: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl MaximumConstraint {
+    pub fn new(result: Rc<RefCell<Variable>>, xs: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert!(!xs.is_empty());
+        Self { result, xs }
+    }
+}
+
+// value of result = min(xs) or max(xs), if everything is assigned
+fn extremum_satisfied(result: &Rc<RefCell<Variable>>, xs: &[Rc<RefCell<Variable>>], max: bool) -> bool {
+    let mut values = Vec::with_capacity(xs.len());
+    for x in xs {
+        match x.borrow().try_value() {
+            Some(v) => values.push(v),
+            None => {
+                return false;
+            }
+        }
+    }
+    let expected = if max {
+        values.iter().max()
+    } else {
+        values.iter().min()
+    };
+    result.borrow().try_value().as_ref() == expected
+}
+
+fn create_extremum_propagator(
+    solver: &mut Solver,
+    result: &Rc<RefCell<Variable>>,
+    xs: &[Rc<RefCell<Variable>>],
+    max: bool,
+) {
+    let p = Rc::new(RefCell::new(MinMaxPropagator::new(
+        result.clone(),
+        xs.to_vec(),
+        max,
+        solver.new_propagator_id(),
+    )));
+    solver.add_propagator(p.clone());
+    p.borrow().listen(p.clone());
+}
+
+impl Constraint for MinimumConstraint {
+    fn satisfied(&self) -> bool {
+        extremum_satisfied(&self.result, &self.xs, false)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        create_extremum_propagator(solver, &self.result, &self.xs, false);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.result.clone()];
+        vars.extend(self.xs.iter().cloned());
+        vars
+    }
+}
+
+impl Constraint for MaximumConstraint {
+    fn satisfied(&self) -> bool {
+        extremum_satisfied(&self.result, &self.xs, true)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        create_extremum_propagator(solver, &self.result, &self.xs, true);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.result.clone()];
+        vars.extend(self.xs.iter().cloned());
+        vars
+    }
+}
+
+/// bounds consistent propagator for result = min(xs), or result = max(xs) if max is set
+/// the maximum is handled as the minimum of negated values
+pub struct MinMaxPropagator {
+    pcb: PropagatorControlBlock,
+    result: Rc<RefCell<Variable>>,
+    xs: Vec<Rc<RefCell<Variable>>>,
+    max: bool,
+}
+
+impl MinMaxPropagator {
+    pub fn new(
+        result: Rc<RefCell<Variable>>,
+        xs: Vec<Rc<RefCell<Variable>>>,
+        max: bool,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            result,
+            xs,
+            max,
+        }
+    }
+
+    // bounds of the variable with values negated for the maximum
+    fn bounds(&self, v: &Rc<RefCell<Variable>>) -> (i64, i64) {
+        let v = v.borrow();
+        if self.max {
+            (-v.get_ub(), -v.get_lb())
+        } else {
+            (v.get_lb(), v.get_ub())
+        }
+    }
+
+    fn tighten(&self, v: &Rc<RefCell<Variable>>, lb: i64, ub: i64) -> bool {
+        if self.max {
+            v.borrow_mut().tighten(-ub, -lb)
+        } else {
+            v.borrow_mut().tighten(lb, ub)
+        }
+    }
+}
+
+impl Propagator for MinMaxPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in self.xs.iter().chain(std::iter::once(&self.result)) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let bounds: Vec<(i64, i64)> = self.xs.iter().map(|x| self.bounds(x)).collect();
+        let min_lb = bounds.iter().map(|b| b.0).min().unwrap();
+        let min_ub = bounds.iter().map(|b| b.1).min().unwrap();
+        if !self.tighten(&self.result, min_lb, min_ub) {
+            return;
+        }
+        let (lb, ub) = self.bounds(&self.result);
+        // every element is at least the minimum
+        for x in &self.xs {
+            if !self.tighten(x, lb, i64::MAX) {
+                return;
+            }
+        }
+        // if only one element can be as small as result, it is the minimum
+        let mut candidates = bounds.iter().enumerate().filter(|(_, b)| b.0 <= ub);
+        if let (Some((i, _)), None) = (candidates.next(), candidates.next()) {
+            self.tighten(&self.xs[i], -i64::MAX, ub);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.xs.len()
+    }
+}
+
+/// z = x * y
+pub struct TimesConstraint {
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    z: Rc<RefCell<Variable>>,
+}
+
+impl TimesConstraint {
+    pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>, z: Rc<RefCell<Variable>>) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Constraint for TimesConstraint {
+    fn satisfied(&self) -> bool {
+        match (
+            self.x.borrow().try_value(),
+            self.y.borrow().try_value(),
+            self.z.borrow().try_value(),
+        ) {
+            (Some(x), Some(y), Some(z)) => (x as i128) * (y as i128) == z as i128,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(TimesPropagator::new(
+            self.x.clone(),
+            self.y.clone(),
+            self.z.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone(), self.z.clone()]
+    }
+}
+
+fn clamp(x: i128) -> i64 {
+    x.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+// bounds of {p / q : p in [p_lb, p_ub], q in [q_lb, q_ub]} rounded inwards, q_lb..q_ub must not contain 0
+fn quotient_bounds(p_lb: i64, p_ub: i64, q_lb: i64, q_ub: i64) -> (i64, i64) {
+    let mut lb = i64::MAX;
+    let mut ub = i64::MIN;
+    for p in [p_lb, p_ub] {
+        for q in [q_lb, q_ub] {
+            let (p, q) = if q < 0 { (-p, -q) } else { (p, q) };
+            lb = i64::min(lb, ceil_div(p, q));
+            ub = i64::max(ub, floor_div(p, q));
+        }
+    }
+    (lb, ub)
+}
+
+/// bounds propagation, z is bounded by products of the bounds of x and y,
+/// x and y by quotients when the divisor can not be zero
+pub struct TimesPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    z: Rc<RefCell<Variable>>,
+}
+
+impl TimesPropagator {
+    pub fn new(
+        x: Rc<RefCell<Variable>>,
+        y: Rc<RefCell<Variable>>,
+        z: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+            z,
+        }
+    }
+
+    // a = b / c, b = a * c
+    fn divide(a: &Rc<RefCell<Variable>>, b: &Rc<RefCell<Variable>>, c: &Rc<RefCell<Variable>>) -> bool {
+        let (c_lb, c_ub) = (c.borrow().get_lb(), c.borrow().get_ub());
+        if c_lb <= 0 && 0 <= c_ub {
+            return true;
+        }
+        let (b_lb, b_ub) = (b.borrow().get_lb(), b.borrow().get_ub());
+        let (lb, ub) = quotient_bounds(b_lb, b_ub, c_lb, c_ub);
+        a.borrow_mut().tighten(lb, ub)
+    }
+}
+
+impl Propagator for TimesPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in [&self.x, &self.y, &self.z] {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let (x_lb, x_ub) = (self.x.borrow().get_lb(), self.x.borrow().get_ub());
+        let (y_lb, y_ub) = (self.y.borrow().get_lb(), self.y.borrow().get_ub());
+        let corners = [
+            (x_lb as i128) * (y_lb as i128),
+            (x_lb as i128) * (y_ub as i128),
+            (x_ub as i128) * (y_lb as i128),
+            (x_ub as i128) * (y_ub as i128),
+        ];
+        let lb = clamp(*corners.iter().min().unwrap());
+        let ub = clamp(*corners.iter().max().unwrap());
+        if !self.z.borrow_mut().tighten(lb, ub) {
+            return;
+        }
+        // a nonzero product has nonzero factors, only bounds can be moved away from zero
+        if !self.z.borrow().possible(0) {
+            for v in [&self.x, &self.y] {
+                let mut v = v.borrow_mut();
+                if v.get_lb() == 0 && !v.set_lb(1) {
+                    return;
+                }
+                if v.get_ub() == 0 && !v.set_ub(-1) {
+                    return;
+                }
+            }
+        }
+        if !Self::divide(&self.x, &self.z, &self.y) {
+            return;
+        }
+        Self::divide(&self.y, &self.z, &self.x);
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_HIGH
+    }
+}
+
+/// y = |x|
+pub struct AbsConstraint {
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    ac: bool,
+}
+
+impl AbsConstraint {
+    /// bounds propagation
+    pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>) -> Self {
+        Self::with_ac(x, y, false)
+    }
+    /// ac selects domain consistency, which iterates over both domains at every run
+    pub fn with_ac(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>, ac: bool) -> Self {
+        Self { x, y, ac }
+    }
+}
+
+impl Constraint for AbsConstraint {
+    fn satisfied(&self) -> bool {
+        match (self.x.borrow().try_value(), self.y.borrow().try_value()) {
+            (Some(x), Some(y)) => (x as i128).abs() == y as i128,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let id = solver.new_propagator_id();
+        let p: Rc<RefCell<dyn Propagator>> = if self.ac {
+            Rc::new(RefCell::new(AbsACPropagator::new(
+                self.x.clone(),
+                self.y.clone(),
+                id,
+            )))
+        } else {
+            Rc::new(RefCell::new(AbsPropagator::new(
+                self.x.clone(),
+                self.y.clone(),
+                id,
+            )))
+        };
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
+}
+
+/// bounds propagation, y is bounded by the absolute values of the bounds of x,
+/// x by [-y.ub, y.ub] without the values of absolute value below y.lb next to its bounds
+pub struct AbsPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+}
+
+impl AbsPropagator {
+    pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+        }
+    }
+}
+
+impl Propagator for AbsPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in [&self.x, &self.y] {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let (x_lb, x_ub) = (self.x.borrow().get_lb(), self.x.borrow().get_ub());
+        let y_lb = if x_lb >= 0 {
+            x_lb
+        } else if x_ub <= 0 {
+            -x_ub
+        } else {
+            0
+        };
+        let y_ub = clamp(i128::max((x_lb as i128).abs(), (x_ub as i128).abs()));
+        if !self.y.borrow_mut().tighten(y_lb, y_ub) {
+            return;
+        }
+        let (y_lb, y_ub) = (self.y.borrow().get_lb(), self.y.borrow().get_ub());
+        let mut x = self.x.borrow_mut();
+        if !x.tighten(-y_ub, y_ub) {
+            return;
+        }
+        // values in (-y.lb, y.lb) are excluded, so a bound inside jumps over the gap
+        if x.get_lb() > -y_lb && !x.set_lb(y_lb) {
+            return;
+        }
+        if x.get_ub() < y_lb {
+            x.set_ub(-y_lb);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_HIGH
+    }
+}
+
+/// domain consistency, x keeps the values whose absolute value is in y,
+/// y keeps the values v such that v or -v is in x
+pub struct AbsACPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+}
+
+impl AbsACPropagator {
+    pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+        }
+    }
+}
+
+impl Propagator for AbsACPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in [&self.x, &self.y] {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let unsupported: Vec<i64> = {
+            let (x, y) = (self.x.borrow(), self.y.borrow());
+            x.iter()
+                .filter(|v| v.checked_abs().is_none_or(|a| !y.possible(a)))
+                .collect()
+        };
+        for v in unsupported {
+            if !self.x.borrow_mut().remove(v) {
+                return;
+            }
+        }
+        let unsupported: Vec<i64> = {
+            let (x, y) = (self.x.borrow(), self.y.borrow());
+            y.iter()
+                .filter(|v| *v < 0 || !(x.possible(*v) || x.possible(-*v)))
+                .collect()
+        };
+        for v in unsupported {
+            if !self.y.borrow_mut().remove(v) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    // every value of x left has its absolute value in y, which is kept by the second pass
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        (self.x.borrow().size() + self.y.borrow().size()) as usize
+    }
+}
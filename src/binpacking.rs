@@ -31,17 +31,13 @@ impl Constraint for BinPackingConstraint {
     fn satisfied(&self) -> bool {
         let mut load = vec![0; self.load.len()];
         for (i, var) in self.assignment.iter().enumerate() {
-            if !var.borrow().is_assigned() {
+            let Some(bin) = var.borrow().try_value() else {
                 return false;
-            }
-            let bin = var.borrow().value();
+            };
             load[bin as usize] += self.weight[i];
         }
         for (i, var) in self.load.iter().enumerate() {
-            if !var.borrow().is_assigned() {
-                return false;
-            }
-            if load[i] != var.borrow().value() {
+            if var.borrow().try_value() != Some(load[i]) {
                 return false;
             }
         }
@@ -58,6 +54,10 @@ impl Constraint for BinPackingConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.assignment.iter().chain(self.load.iter()).cloned().collect()
+    }
 }
 
 pub struct BinPackingPropagator {
@@ -215,8 +215,9 @@ impl Propagator for BinPackingPropagator {
         let mut required_sum = vec![0; bins];
 
         for i in 0..items {
-            if self.assignment[i].borrow().is_assigned() {
-                let bin = self.assignment[i].borrow().value() as usize;
+            let fixed = self.assignment[i].borrow().try_value();
+            if let Some(bin) = fixed {
+                let bin = bin as usize;
                 required[bin].push(i);
                 required_sum[bin] += self.weight[i];
                 possible[bin].push(i);
@@ -232,8 +233,7 @@ impl Propagator for BinPackingPropagator {
 
         for j in 0..bins {
             let mut load = self.load[j].borrow_mut();
-            load.set_lb(required_sum[j]);
-            load.set_ub(possible_sum[j]);
+            load.tighten(required_sum[j], possible_sum[j]);
         }
 
         let mut upper_sum = 0;
@@ -247,8 +247,10 @@ impl Propagator for BinPackingPropagator {
             let mut load = self.load[j].borrow_mut();
             let lb = load.get_lb();
             let ub = load.get_ub();
-            load.set_lb(self.total_weight - upper_sum + ub);
-            load.set_ub(self.total_weight - lower_sum + lb);
+            load.tighten(
+                self.total_weight - upper_sum + ub,
+                self.total_weight - lower_sum + lb,
+            );
         }
 
         for j in 0..bins {
@@ -378,4 +380,200 @@ impl Propagator for BinPackingPropagator {
     fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
         &mut self.pcb
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.assignment.len() * self.load.len()
+    }
+}
+
+/// same as BinPackingConstraint, but the weights are variables, they have to be non-negative
+/// the propagator only reasons on bounds of loads and weights, the subset sum pruning
+/// and the bin count bound of BinPackingPropagator apply to fixed weights only
+pub struct VarBinPackingConstraint {
+    assignment: Vec<Rc<RefCell<Variable>>>,
+    load: Vec<Rc<RefCell<Variable>>>,
+    weight: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl VarBinPackingConstraint {
+    pub fn new(
+        assignment: Vec<Rc<RefCell<Variable>>>,
+        load: Vec<Rc<RefCell<Variable>>>,
+        weight: Vec<Rc<RefCell<Variable>>>,
+    ) -> Self {
+        assert!(assignment.len() == weight.len());
+        Self {
+            assignment,
+            load,
+            weight,
+        }
+    }
+}
+
+impl Constraint for VarBinPackingConstraint {
+    fn satisfied(&self) -> bool {
+        let mut load = vec![0; self.load.len()];
+        for (var, weight) in self.assignment.iter().zip(self.weight.iter()) {
+            let (Some(bin), Some(weight)) = (var.borrow().try_value(), weight.borrow().try_value())
+            else {
+                return false;
+            };
+            if bin < 0 || bin as usize >= load.len() || weight < 0 {
+                return false;
+            }
+            load[bin as usize] += weight;
+        }
+        for (i, var) in self.load.iter().enumerate() {
+            if var.borrow().try_value() != Some(load[i]) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(VarBinPackingPropagator::new(
+            self.assignment.clone(),
+            self.load.clone(),
+            self.weight.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.assignment
+            .iter()
+            .chain(self.load.iter())
+            .chain(self.weight.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+/// interval reasoning, a bin holds at least the smallest weights of the items assigned to it
+/// and at most the largest weights of the items which can go there
+pub struct VarBinPackingPropagator {
+    pcb: PropagatorControlBlock,
+    assignment: Vec<Rc<RefCell<Variable>>>,
+    load: Vec<Rc<RefCell<Variable>>>,
+    weight: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl VarBinPackingPropagator {
+    pub fn new(
+        assignment: Vec<Rc<RefCell<Variable>>>,
+        load: Vec<Rc<RefCell<Variable>>>,
+        weight: Vec<Rc<RefCell<Variable>>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            assignment,
+            load,
+            weight,
+        }
+    }
+}
+
+impl Propagator for VarBinPackingPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.assignment {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+        for v in self.load.iter().chain(self.weight.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let bins = self.load.len();
+        let mut required_sum = vec![0; bins];
+        let mut possible_sum = vec![0; bins];
+        let mut total_lb = 0;
+        let mut total_ub = 0;
+        for (var, weight) in self.assignment.iter().zip(self.weight.iter()) {
+            if !var.borrow_mut().tighten(0, bins as i64 - 1) || !weight.borrow_mut().set_lb(0) {
+                return;
+            }
+            let (w_lb, w_ub) = (weight.borrow().get_lb(), weight.borrow().get_ub());
+            total_lb += w_lb;
+            total_ub += w_ub;
+            let var = var.borrow();
+            if let Some(bin) = var.try_value() {
+                required_sum[bin as usize] += w_lb;
+            }
+            for bin in var.iter() {
+                possible_sum[bin as usize] += w_ub;
+            }
+        }
+
+        for j in 0..bins {
+            if !self.load[j]
+                .borrow_mut()
+                .tighten(required_sum[j], possible_sum[j])
+            {
+                return;
+            }
+        }
+        // the loads sum up to the total weight
+        let lower_sum: i64 = self.load.iter().map(|l| l.borrow().get_lb()).sum();
+        let upper_sum: i64 = self.load.iter().map(|l| l.borrow().get_ub()).sum();
+        for load in &self.load {
+            let mut load = load.borrow_mut();
+            let (lb, ub) = (load.get_lb(), load.get_ub());
+            if !load.tighten(total_lb - upper_sum + ub, total_ub - lower_sum + lb) {
+                return;
+            }
+        }
+
+        for (var, weight) in self.assignment.iter().zip(self.weight.iter()) {
+            let (w_lb, w_ub) = (weight.borrow().get_lb(), weight.borrow().get_ub());
+            if let Some(bin) = var.borrow().try_value() {
+                // the other items of the bin take up at least required - w_lb and at most possible - w_ub
+                let load = self.load[bin as usize].borrow();
+                let rest_lb = required_sum[bin as usize] - w_lb;
+                let rest_ub = possible_sum[bin as usize] - w_ub;
+                if !weight
+                    .borrow_mut()
+                    .tighten(load.get_lb() - rest_ub, load.get_ub() - rest_lb)
+                {
+                    return;
+                }
+                continue;
+            }
+            let candidates: Vec<i64> = var.borrow().iter().collect();
+            for bin in candidates {
+                let load = &self.load[bin as usize];
+                let (l_lb, l_ub) = (load.borrow().get_lb(), load.borrow().get_ub());
+                if required_sum[bin as usize] + w_lb > l_ub && !var.borrow_mut().remove(bin) {
+                    return;
+                }
+                // the bin can not be filled without this item
+                if possible_sum[bin as usize] - w_ub < l_lb {
+                    if !var.borrow_mut().assign(bin) {
+                        return;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.assignment.len() * self.load.len()
+    }
 }
@@ -1,4 +1,4 @@
-use crate::domain::{Domain, DomainState};
+use crate::domain::{nth_set_bit, Domain, DomainState};
 use crate::solver::SolverState;
 use std::boxed::Box;
 use std::cell::RefCell;
@@ -17,6 +17,34 @@ pub struct BitsetDomain {
 }
 
 impl BitsetDomain {
+    /// domain holding the union of the closed intervals [l, r] in ranges
+    pub fn from_ranges(solver_state: Rc<RefCell<SolverState>>, ranges: &[(i64, i64)]) -> Self {
+        assert!(!ranges.is_empty() && ranges.iter().all(|(l, r)| l <= r));
+        let lb = ranges.iter().map(|(l, _)| *l).min().unwrap();
+        let ub = ranges.iter().map(|(_, r)| *r).max().unwrap();
+        let mut domain = Self::new(solver_state, lb, ub);
+        domain.data.fill(0);
+        for (l, r) in ranges.iter().cloned() {
+            // bits of [x, r] are set one word at a time
+            let mut x = l;
+            while x <= r {
+                let id = (x - lb) as u64;
+                let block = (id / 64) as usize;
+                let shift = id % 64;
+                let len = u64::min(64 - shift, (r - x + 1) as u64);
+                let mask = if len == 64 {
+                    u64::MAX
+                } else {
+                    ((1u64 << len) - 1) << shift
+                };
+                domain.data[block] |= mask;
+                x += len as i64;
+            }
+        }
+        domain.size = domain.data.iter().map(|w| w.count_ones() as u64).sum();
+        domain
+    }
+
     fn save(&mut self, block: usize) {
         if self.modified[block] >= self.trail.len() || self.trail[self.modified[block]].0 != block {
             self.modified[block] = self.trail.len();
@@ -138,11 +166,18 @@ impl Domain for BitsetDomain {
             self.solver_state.borrow_mut().fail();
             return DomainState::Failed;
         }
-        while self.data[self.first_block] == 0 {
-            self.first_block += 1;
-        }
-        while self.data[self.last_block] == 0 {
-            self.last_block -= 1;
+        // bound blocks only move when the block holding the bound becomes empty
+        if self.data[block] == 0 {
+            if block == self.first_block {
+                while self.data[self.first_block] == 0 {
+                    self.first_block += 1;
+                }
+            }
+            if block == self.last_block {
+                while self.data[self.last_block] == 0 {
+                    self.last_block -= 1;
+                }
+            }
         }
         DomainState::Modified
     }
@@ -235,6 +270,52 @@ impl Domain for BitsetDomain {
         DomainState::Same
     }
 
+    fn tighten(&mut self, lb: i64, ub: i64) -> DomainState {
+        let end = self.start + (self.data.len() as i64) * 64;
+        if lb > ub || ub < self.start || lb >= end {
+            self.solver_state.borrow_mut().fail();
+            return DomainState::Failed;
+        }
+        let lo = (i64::max(lb, self.start) - self.start) as u64;
+        let hi = (i64::min(ub, end - 1) - self.start) as u64;
+        let lo_block = (lo / 64) as usize;
+        let hi_block = (hi / 64) as usize;
+        let old_size = self.size;
+        for i in self.first_block..self.last_block + 1 {
+            let mut mask = 0;
+            if lo_block <= i && i <= hi_block {
+                mask = u64::MAX;
+                if i == lo_block {
+                    mask &= u64::MAX << (lo % 64);
+                }
+                if i == hi_block {
+                    mask &= u64::MAX >> (63 - hi % 64);
+                }
+            }
+            let removed = self.data[i] & !mask;
+            if removed != 0 {
+                self.save(i);
+                self.size -= removed.count_ones() as u64;
+                self.data[i] &= mask;
+            }
+        }
+        if self.size == 0 {
+            self.solver_state.borrow_mut().fail();
+            return DomainState::Failed;
+        }
+        while self.data[self.first_block] == 0 {
+            self.first_block += 1;
+        }
+        while self.data[self.last_block] == 0 {
+            self.last_block -= 1;
+        }
+        if old_size != self.size {
+            DomainState::Modified
+        } else {
+            DomainState::Same
+        }
+    }
+
     fn checkpoint(&mut self) {
         self.checkpoints.push(self.trail.drain(..).collect());
     }
@@ -269,4 +350,20 @@ impl Domain for BitsetDomain {
     fn size(&self) -> u64 {
         self.size
     }
+
+    fn nth_present(&self, mut k: u64) -> i64 {
+        assert!(k < self.size, "k is out of range");
+        for i in self.first_block..self.last_block + 1 {
+            let count = self.data[i].count_ones() as u64;
+            if k < count {
+                return self.start + 64 * i as i64 + nth_set_bit(self.data[i], k as u32) as i64;
+            }
+            k -= count;
+        }
+        unreachable!()
+    }
+
+    fn bitmap(&self) -> Option<(i64, &[u64])> {
+        Some((self.start, self.data.as_slice()))
+    }
 }
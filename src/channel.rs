@@ -0,0 +1,262 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// x[i] = j <=> b[i][j] = 1
+/// each b[i] is a one-hot encoding of x[i], values of x[i] are 0, ..., b[i].len() - 1
+pub struct BoolChannelConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    b: Vec<Vec<Rc<RefCell<Variable>>>>,
+}
+
+impl BoolChannelConstraint {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, b: Vec<Vec<Rc<RefCell<Variable>>>>) -> Self {
+        assert!(x.len() == b.len());
+        Self { x, b }
+    }
+}
+
+impl Constraint for BoolChannelConstraint {
+    fn satisfied(&self) -> bool {
+        for (x, row) in self.x.iter().zip(self.b.iter()) {
+            let Some(x) = x.borrow().try_value() else {
+                return false;
+            };
+            if x < 0 || x >= row.len() as i64 {
+                return false;
+            }
+            for (j, b) in row.iter().enumerate() {
+                match b.borrow().try_value() {
+                    Some(1) if x == j as i64 => {}
+                    Some(0) if x != j as i64 => {}
+                    _ => {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        for (x, row) in self.x.iter().zip(self.b.iter()) {
+            let p = Rc::new(RefCell::new(BoolChannelPropagator::new(
+                x.clone(),
+                row.clone(),
+                solver.new_propagator_id(),
+            )));
+            solver.add_propagator(p.clone());
+            p.borrow().listen(p.clone());
+        }
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.x.clone();
+        for row in &self.b {
+            vars.extend(row.iter().cloned());
+        }
+        vars
+    }
+}
+
+/// channels a single row: x = j <=> b[j] = 1
+pub struct BoolChannelPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    b: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl BoolChannelPropagator {
+    pub fn new(x: Rc<RefCell<Variable>>, b: Vec<Rc<RefCell<Variable>>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            b,
+        }
+    }
+}
+
+impl Propagator for BoolChannelPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.x
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+        for b in &self.b {
+            b.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        if !self.x.borrow_mut().tighten(0, self.b.len() as i64 - 1) {
+            return;
+        }
+        for (j, b) in self.b.iter().enumerate() {
+            if !b.borrow_mut().tighten(0, 1) {
+                return;
+            }
+            let fixed = b.borrow().try_value();
+            let ok = match fixed {
+                Some(0) => self.x.borrow_mut().remove(j as i64),
+                Some(_) => self.x.borrow_mut().assign(j as i64),
+                None => true,
+            };
+            if !ok {
+                return;
+            }
+        }
+        // x is final now, copy it back to the booleans
+        for (j, b) in self.b.iter().enumerate() {
+            let ok = if !self.x.borrow().possible(j as i64) {
+                b.borrow_mut().remove(1)
+            } else if self.x.borrow().is_assigned() {
+                b.borrow_mut().assign(1)
+            } else {
+                true
+            };
+            if !ok {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.b.len()
+    }
+}
+
+/// x[i] = j <=> y[j] = i
+/// x and y are inverse permutations of 0, ..., n - 1
+pub struct InverseConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl InverseConstraint {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, y: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert!(x.len() == y.len());
+        Self { x, y }
+    }
+}
+
+impl Constraint for InverseConstraint {
+    fn satisfied(&self) -> bool {
+        let n = self.x.len() as i64;
+        for (i, x) in self.x.iter().enumerate() {
+            let Some(j) = x.borrow().try_value() else {
+                return false;
+            };
+            if j < 0 || j >= n || self.y[j as usize].borrow().try_value() != Some(i as i64) {
+                return false;
+            }
+        }
+        // x is a permutation now, so every y[j] was checked
+        true
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(InversePropagator::new(
+            self.x.clone(),
+            self.y.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.x.clone();
+        vars.extend(self.y.iter().cloned());
+        vars
+    }
+}
+
+/// removes j from x[i] whenever i is not in y[j] and vice versa
+pub struct InversePropagator {
+    pcb: PropagatorControlBlock,
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl InversePropagator {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, y: Vec<Rc<RefCell<Variable>>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+        }
+    }
+
+    // a -> b direction: values of a[i] without support in b are removed, assigned a[i] = j forces b[j] = i
+    fn channel(a: &[Rc<RefCell<Variable>>], b: &[Rc<RefCell<Variable>>]) -> bool {
+        let n = b.len() as i64;
+        for (i, v) in a.iter().enumerate() {
+            if !v.borrow_mut().tighten(0, n - 1) {
+                return false;
+            }
+            for (j, w) in b.iter().enumerate() {
+                if w.borrow().possible(i as i64) {
+                    continue;
+                }
+                if !v.borrow_mut().remove(j as i64) {
+                    return false;
+                }
+            }
+            let fixed = v.borrow().try_value();
+            if let Some(j) = fixed {
+                if !b[j as usize].borrow_mut().assign(i as i64) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Propagator for InversePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in self.x.iter().chain(self.y.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        if !Self::channel(&self.x, &self.y) {
+            return;
+        }
+        Self::channel(&self.y, &self.x);
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+}
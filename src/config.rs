@@ -0,0 +1,108 @@
+use crate::value_selector::{MinValueSelector, RandomValueSelector, ValueSelector};
+use crate::variable::Variable;
+use crate::variable_selector::{
+    DomWDegVariableSelector, FirstFailVariableSelector, LexVariableSelector, VariableSelector,
+};
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// when the search is restarted from the root
+/// restarts only change the explored part of the tree if the search is randomized,
+/// as nothing is learned from the failures before a restart
+pub enum RestartStrategy {
+    None,
+    /// restart after base * luby(i) failures, where luby is 1, 1, 2, 1, 1, 2, 4, 1, ..., base has to be positive
+    Luby { base: usize },
+    /// restart after base * factor^i failures, rounded up, base has to be positive and factor greater than 1
+    /// this is only useful with a randomized value or variable selector
+    Geometric { base: usize, factor: f64 },
+}
+
+/// receives all variables of the solver, in the order of their creation
+pub type SolutionCallback = Box<dyn FnMut(&[Rc<RefCell<Variable>>])>;
+
+/// search settings of the solver
+pub struct Config {
+    pub variable_selector: Box<dyn VariableSelector>,
+    pub value_selector: Box<dyn ValueSelector>,
+    /// stop propagating at a search node after this many propagator runs,
+    /// the rest of the propagation queue is processed at the next node
+    /// nodes are pruned less, so search may explore more of the tree,
+    /// but full assignments are checked against all constraints, so solutions stay correct
+    pub max_propagations_per_node: Option<usize>,
+    /// when optimizing, stop once this much time has passed since the last improving solution
+    /// the best solution found is still returned, but it is not proven to be optimal
+    pub proof_time_limit: Option<Duration>,
+    /// stop once this much time has passed since the start of solve()
+    /// the limit is checked between search nodes, so a long propagation can overrun it
+    pub time_limit: Option<Duration>,
+    /// stop after exploring this many search nodes
+    pub node_limit: Option<usize>,
+    /// stop after this many failures
+    pub fail_limit: Option<usize>,
+    /// AllDifferent constraints switch from arc consistency to bounds consistency
+    /// whenever their value graph could have more vertices than this,
+    /// the count is estimated as the number of variables plus the span of the union of domains,
+    /// Some(0) always uses bounds consistency
+    pub alldifferent_ac_max_vertices: Option<usize>,
+    pub restart_strategy: RestartStrategy,
+    /// called whenever a solution is accepted: every solution of a satisfaction search,
+    /// every improving solution when optimizing
+    /// the variables are still assigned to the solution during the call
+    pub on_solution: Option<SolutionCallback>,
+}
+
+impl Config {
+    pub fn new(
+        variable_selector: Box<dyn VariableSelector>,
+        value_selector: Box<dyn ValueSelector>,
+    ) -> Self {
+        Self {
+            variable_selector,
+            value_selector,
+            max_propagations_per_node: None,
+            proof_time_limit: None,
+            time_limit: None,
+            node_limit: None,
+            fail_limit: None,
+            alldifferent_ac_max_vertices: None,
+            restart_strategy: RestartStrategy::None,
+            on_solution: None,
+        }
+    }
+    /// first-fail variable selection, smallest value first
+    pub fn default_cp() -> Self {
+        Self::new(
+            Box::new(FirstFailVariableSelector {}),
+            Box::new(MinValueSelector {}),
+        )
+    }
+    /// dom/wdeg variable selection, random values and Luby restarts,
+    /// the random values make each restart explore a different part of the tree
+    /// the sequence of choices is determined by the seed
+    pub fn dom_wdeg_restart(seed: u64) -> Self {
+        Self {
+            restart_strategy: RestartStrategy::Luby { base: 32 },
+            ..Self::new(
+                Box::new(DomWDegVariableSelector {}),
+                Box::new(RandomValueSelector::new(seed)),
+            )
+        }
+    }
+    /// variables in the order of creation, smallest value first,
+    /// so solve_all() returns solutions in lexicographic order
+    pub fn enumerate_all() -> Self {
+        Self::new(
+            Box::new(LexVariableSelector {}),
+            Box::new(MinValueSelector {}),
+        )
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::default_cp()
+    }
+}
@@ -1,7 +1,28 @@
 use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub trait Constraint {
     fn satisfied(&self) -> bool;
     /// this function is run whenever the constraint is added to solver
     fn create_propagators(&self, solver: &mut Solver);
+    /// variables in the scope of the constraint, empty by default
+    /// the scope is used by dom/wdeg weights, dump_model and check_gac,
+    /// a constraint with an empty scope is invisible to them
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        Vec::new()
+    }
+    /// short name used in diagnostics, the type name without module path by default
+    fn name(&self) -> String {
+        let full = std::any::type_name::<Self>();
+        let base = full.split('<').next().unwrap_or(full);
+        base.rsplit("::").next().unwrap_or(base).to_string()
+    }
+
+    /// constraint holding exactly when this one is violated, over the same variables
+    /// None if there is no such constraint at hand
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        None
+    }
 }
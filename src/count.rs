@@ -0,0 +1,654 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{max_domain_size, Propagator, PropagatorControlBlock};
+use crate::solver::{Solver, SolverState};
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+// number of vars assigned to value
+fn count_assigned(vars: &[Rc<RefCell<Variable>>], value: i64) -> Option<i64> {
+    let mut count = 0;
+    for v in vars {
+        if v.borrow().try_value()? == value {
+            count += 1;
+        }
+    }
+    Some(count)
+}
+
+/// count = number of i such that vars[i] = value
+pub struct CountConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    count: Rc<RefCell<Variable>>,
+}
+
+impl CountConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, value: i64, count: Rc<RefCell<Variable>>) -> Self {
+        Self { vars, value, count }
+    }
+}
+
+impl Constraint for CountConstraint {
+    fn satisfied(&self) -> bool {
+        let count = count_assigned(&self.vars, self.value);
+        count.is_some() && self.count.borrow().try_value() == count
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(CountPropagator::new(
+            self.vars.clone(),
+            self.value,
+            self.count.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.vars.clone();
+        vars.push(self.count.clone());
+        vars
+    }
+}
+
+pub struct CountPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    count: Rc<RefCell<Variable>>,
+}
+
+impl CountPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        value: i64,
+        count: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            value,
+            count,
+        }
+    }
+}
+
+impl Propagator for CountPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+        self.count
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        // variables assigned to the value, and those which can still take it
+        let mut assigned = 0;
+        let mut possible = 0;
+        for v in &self.vars {
+            let v = v.borrow();
+            if v.possible(self.value) {
+                possible += 1;
+                if v.is_assigned() {
+                    assigned += 1;
+                }
+            }
+        }
+        let mut count = self.count.borrow_mut();
+        if !count.tighten(assigned, possible) {
+            return;
+        }
+        let (lb, ub) = (count.get_lb(), count.get_ub());
+        drop(count);
+        if assigned == possible {
+            return;
+        }
+        if ub == assigned {
+            for v in &self.vars {
+                let mut v = v.borrow_mut();
+                if !v.is_assigned() && !v.remove(self.value) {
+                    return;
+                }
+            }
+        } else if lb == possible {
+            for v in &self.vars {
+                let mut v = v.borrow_mut();
+                if v.possible(self.value) && !v.assign(self.value) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
+
+/// at most bound of vars are equal to value
+pub struct AtMostConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    bound: i64,
+}
+
+impl AtMostConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, value: i64, bound: i64) -> Self {
+        Self { vars, value, bound }
+    }
+}
+
+impl Constraint for AtMostConstraint {
+    fn satisfied(&self) -> bool {
+        count_assigned(&self.vars, self.value).is_some_and(|count| count <= self.bound)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(AtMostPropagator::new(
+            self.vars.clone(),
+            self.value,
+            self.bound,
+            solver.solver_state(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// removes value from the other variables once bound variables are assigned to it
+pub struct AtMostPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    bound: i64,
+    // failures go here, as there may be no variables to fail through
+    solver_state: Rc<RefCell<SolverState>>,
+}
+
+impl AtMostPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        value: i64,
+        bound: i64,
+        solver_state: Rc<RefCell<SolverState>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            value,
+            bound,
+            solver_state,
+        }
+    }
+}
+
+impl Propagator for AtMostPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Assigned);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let assigned = self
+            .vars
+            .iter()
+            .filter(|v| v.borrow().try_value() == Some(self.value))
+            .count() as i64;
+        if assigned > self.bound {
+            self.solver_state.borrow_mut().fail();
+            return;
+        }
+        if assigned < self.bound {
+            return;
+        }
+        for v in &self.vars {
+            let mut v = v.borrow_mut();
+            if !v.is_assigned() && !v.remove(self.value) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
+
+/// at least bound of vars are equal to value
+pub struct AtLeastConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    bound: i64,
+}
+
+impl AtLeastConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, value: i64, bound: i64) -> Self {
+        Self { vars, value, bound }
+    }
+}
+
+impl Constraint for AtLeastConstraint {
+    fn satisfied(&self) -> bool {
+        count_assigned(&self.vars, self.value).is_some_and(|count| count >= self.bound)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(AtLeastPropagator::new(
+            self.vars.clone(),
+            self.value,
+            self.bound,
+            solver.solver_state(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// assigns value to all remaining candidates when there are just enough of them
+/// only assignments wake it up, so losing a candidate by removal is noticed at the next assignment
+pub struct AtLeastPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    value: i64,
+    bound: i64,
+    // failures go here, as there may be no variables to fail through
+    solver_state: Rc<RefCell<SolverState>>,
+}
+
+impl AtLeastPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        value: i64,
+        bound: i64,
+        solver_state: Rc<RefCell<SolverState>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            value,
+            bound,
+            solver_state,
+        }
+    }
+}
+
+impl Propagator for AtLeastPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Assigned);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let mut assigned = 0;
+        let mut candidates = 0;
+        for v in &self.vars {
+            let v = v.borrow();
+            if v.possible(self.value) {
+                if v.is_assigned() {
+                    assigned += 1;
+                } else {
+                    candidates += 1;
+                }
+            }
+        }
+        let shortfall = self.bound - assigned;
+        if shortfall <= 0 {
+            return;
+        }
+        if candidates < shortfall {
+            self.solver_state.borrow_mut().fail();
+            return;
+        }
+        if candidates > shortfall {
+            return;
+        }
+        for v in &self.vars {
+            let mut v = v.borrow_mut();
+            if v.possible(self.value) && !v.assign(self.value) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
+
+/// nvalue = number of distinct values taken by vars
+pub struct NValueConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    nvalue: Rc<RefCell<Variable>>,
+}
+
+impl NValueConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, nvalue: Rc<RefCell<Variable>>) -> Self {
+        Self { vars, nvalue }
+    }
+}
+
+impl Constraint for NValueConstraint {
+    fn satisfied(&self) -> bool {
+        let mut values = HashSet::new();
+        for v in &self.vars {
+            match v.borrow().try_value() {
+                Some(x) => {
+                    values.insert(x);
+                }
+                None => {
+                    return false;
+                }
+            }
+        }
+        self.nvalue.borrow().try_value() == Some(values.len() as i64)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(NValuePropagator::new(
+            self.vars.clone(),
+            self.nvalue.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.vars.clone();
+        vars.push(self.nvalue.clone());
+        vars
+    }
+}
+
+/// bounds nvalue from below by the size of a set of variables with pairwise disjoint bounds,
+/// picked greedily by upper bound, and from above by the number of variables
+/// and the number of values in the union of the intervals between the bounds
+/// holes are ignored, so a run takes O(n log n) whatever the size of the domains
+pub struct NValuePropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    nvalue: Rc<RefCell<Variable>>,
+}
+
+impl NValuePropagator {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, nvalue: Rc<RefCell<Variable>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            nvalue,
+        }
+    }
+}
+
+impl Propagator for NValuePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let mut bounds: Vec<(i64, i64)> = self
+            .vars
+            .iter()
+            .map(|v| (v.borrow().get_lb(), v.borrow().get_ub()))
+            .collect();
+        bounds.sort_by_key(|(lb, ub)| (*ub, *lb));
+        // greedy choice of pairwise disjoint intervals, each of them needs its own value
+        let mut independent = 0;
+        let mut last_ub = None;
+        for (lb, ub) in bounds.iter().cloned() {
+            if last_ub.is_none_or(|last| lb > last) {
+                independent += 1;
+                last_ub = Some(ub);
+            }
+        }
+        // values in the union of the intervals, computed by their lower bounds
+        bounds.sort();
+        let mut union: i128 = 0;
+        let mut covered_to = None;
+        for (lb, ub) in bounds {
+            let from = match covered_to {
+                Some(c) if c >= lb => c + 1,
+                _ => lb,
+            };
+            if from <= ub {
+                union += (ub as i128) - (from as i128) + 1;
+                covered_to = Some(ub);
+            }
+        }
+        let ub = i128::min(union, self.vars.len() as i128) as i64;
+        self.nvalue.borrow_mut().tighten(independent, ub);
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
+
+/// count = number of i such that vars[i] is in values
+pub struct AmongConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    values: HashSet<i64>,
+    count: Rc<RefCell<Variable>>,
+}
+
+impl AmongConstraint {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        values: HashSet<i64>,
+        count: Rc<RefCell<Variable>>,
+    ) -> Self {
+        Self {
+            vars,
+            values,
+            count,
+        }
+    }
+}
+
+impl Constraint for AmongConstraint {
+    fn satisfied(&self) -> bool {
+        let mut count = 0;
+        for v in &self.vars {
+            match v.borrow().try_value() {
+                Some(x) => {
+                    if self.values.contains(&x) {
+                        count += 1;
+                    }
+                }
+                None => {
+                    return false;
+                }
+            }
+        }
+        self.count.borrow().try_value() == Some(count)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(AmongPropagator::new(
+            self.vars.clone(),
+            self.values.clone(),
+            self.count.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.vars.clone();
+        vars.push(self.count.clone());
+        vars
+    }
+}
+
+pub struct AmongPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    values: HashSet<i64>,
+    count: Rc<RefCell<Variable>>,
+}
+
+impl AmongPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        values: HashSet<i64>,
+        count: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            values,
+            count,
+        }
+    }
+
+    // variables which can go either way keep only the values inside (or outside) the set
+    fn restrict(&self, undecided: &[usize], inside: bool) {
+        for i in undecided.iter().cloned() {
+            let removed: Vec<_> = self.vars[i]
+                .borrow()
+                .iter()
+                .filter(|x| self.values.contains(x) != inside)
+                .collect();
+            for x in removed {
+                if !self.vars[i].borrow_mut().remove(x) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Propagator for AmongPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+        self.count
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        // variables with the whole domain inside the set, the rest of those which can be there
+        let mut forced = 0;
+        let mut undecided = Vec::new();
+        for (i, v) in self.vars.iter().enumerate() {
+            let v = v.borrow();
+            let inside = v.iter().filter(|x| self.values.contains(x)).count() as u64;
+            if inside == v.size() {
+                forced += 1;
+            } else if inside > 0 {
+                undecided.push(i);
+            }
+        }
+        let possible = forced + undecided.len() as i64;
+        let mut count = self.count.borrow_mut();
+        if !count.tighten(forced, possible) {
+            return;
+        }
+        let (lb, ub) = (count.get_lb(), count.get_ub());
+        drop(count);
+        if undecided.is_empty() {
+            return;
+        }
+        if ub == forced {
+            self.restrict(&undecided, false);
+        } else if lb == possible {
+            self.restrict(&undecided, true);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * max_domain_size(&self.vars)
+    }
+}
@@ -22,20 +22,77 @@ pub trait Domain {
     fn get_ub(&self) -> i64;
     fn set_lb(&mut self, x: i64) -> DomainState;
     fn set_ub(&mut self, x: i64) -> DomainState;
+    /// restricts domain to [lb, ub]
+    fn tighten(&mut self, lb: i64, ub: i64) -> DomainState {
+        let lower = self.set_lb(lb);
+        if lower == DomainState::Failed {
+            return DomainState::Failed;
+        }
+        let upper = self.set_ub(ub);
+        if upper == DomainState::Failed {
+            DomainState::Failed
+        } else if lower == DomainState::Modified || upper == DomainState::Modified {
+            DomainState::Modified
+        } else {
+            DomainState::Same
+        }
+    }
     fn checkpoint(&mut self);
     fn rollback(&mut self);
     fn iter(&self) -> Box<dyn Iterator<Item = i64> + '_>;
     fn size(&self) -> u64;
+    /// k-th smallest value in the domain, counting from 0, k has to be less than size()
+    fn nth_present(&self, k: u64) -> i64 {
+        self.iter().nth(k as usize).expect("k is out of range")
+    }
+    /// middle value present in the domain, the lower one for even sizes
+    /// unlike (lb + ub) / 2, it is always in the domain
+    fn get_median(&self) -> i64 {
+        self.nth_present((self.size() - 1) / 2)
+    }
+    /// values packed into words, bit j of word i is set iff start + 64 * i + j is in the domain
+    /// returns (start, words), or None if the domain is not stored as a bitmap
+    fn bitmap(&self) -> Option<(i64, &[u64])> {
+        None
+    }
+    /// maximal intervals of missing values between lb and ub, meant for diagnostics
+    fn holes(&self) -> Vec<(i64, i64)> {
+        let mut ans = Vec::new();
+        let mut it = self.iter();
+        if let Some(mut prev) = it.next() {
+            for x in it {
+                if x > prev + 1 {
+                    ans.push((prev + 1, x - 1));
+                }
+                prev = x;
+            }
+        }
+        ans
+    }
+}
+
+/// position of the k-th set bit of word, counting from 0
+pub(crate) fn nth_set_bit(mut word: u64, k: u32) -> u32 {
+    assert!(k < word.count_ones(), "k is out of range");
+    for _ in 0..k {
+        word &= word - 1;
+    }
+    word.trailing_zeros()
 }
 
 /// implementation for domains which fit in {0, ..., 63}
+/// values are stored relative to the initial lower bound, which never moves,
+/// so values outside of [start, start + 63] are simply never in the domain
 pub struct SmallDomain {
     solver_state: Rc<RefCell<SolverState>>,
     body: u64,
     start: i64,
     lb: u8,
     ub: u8,
-    checkpoints: Vec<(u64, i64, u8, u8)>,
+    // number of open checkpoints
+    depth: usize,
+    // (depth, body before the first change at that depth), a checkpoint without changes costs nothing
+    trail: Vec<(usize, u64)>,
 }
 
 pub struct SmallDomainIterator {
@@ -58,8 +115,16 @@ impl Iterator for SmallDomainIterator {
 }
 
 impl SmallDomain {
+    fn save(&mut self) {
+        if self.trail.last().is_none_or(|(d, _)| *d != self.depth) {
+            self.trail.push((self.depth, self.body));
+        }
+    }
     fn discard(&mut self, x: u8) {
-        self.body &= !((1 as u64) << x);
+        if self.body & (1u64 << x) > 0 {
+            self.save();
+            self.body &= !((1 as u64) << x);
+        }
     }
 }
 
@@ -75,7 +140,8 @@ impl Domain for SmallDomain {
             start: lb,
             lb: 0,
             ub: (ub - lb) as u8,
-            checkpoints: Vec::new(),
+            depth: 0,
+            trail: Vec::new(),
         }
     }
     fn assign(&mut self, x: i64) -> DomainState {
@@ -89,6 +155,9 @@ impl Domain for SmallDomain {
             DomainState::Failed
         } else {
             let modified = self.body != (1 as u64) << v;
+            if modified {
+                self.save();
+            }
             self.body = (1 as u64) << v;
             self.lb = v;
             self.ub = v;
@@ -158,7 +227,11 @@ impl Domain for SmallDomain {
                 }
                 self.discard(i);
             }
-            self.lb = y1;
+            if self.body == 0 {
+                self.solver_state.borrow_mut().fail();
+                return DomainState::Failed;
+            }
+            self.lb = self.body.trailing_zeros() as u8;
         }
         if modified {
             DomainState::Modified
@@ -184,7 +257,11 @@ impl Domain for SmallDomain {
                 }
                 self.discard(i);
             }
-            self.ub = y1;
+            if self.body == 0 {
+                self.solver_state.borrow_mut().fail();
+                return DomainState::Failed;
+            }
+            self.ub = 63 - self.body.leading_zeros() as u8;
         }
         if modified {
             DomainState::Modified
@@ -193,15 +270,18 @@ impl Domain for SmallDomain {
         }
     }
     fn checkpoint(&mut self) {
-        self.checkpoints
-            .push((self.body, self.start, self.lb, self.ub));
+        self.depth += 1;
     }
     fn rollback(&mut self) {
-        let state = self.checkpoints.pop().unwrap();
-        self.body = state.0;
-        self.start = state.1;
-        self.lb = state.2;
-        self.ub = state.3;
+        assert!(self.depth > 0, "rollback without checkpoint");
+        if self.trail.last().is_some_and(|(d, _)| *d == self.depth) {
+            self.body = self.trail.pop().unwrap().1;
+            if self.body != 0 {
+                self.lb = self.body.trailing_zeros() as u8;
+                self.ub = 63 - self.body.leading_zeros() as u8;
+            }
+        }
+        self.depth -= 1;
     }
     fn iter(&self) -> Box<dyn Iterator<Item = i64> + '_> {
         Box::new(SmallDomainIterator {
@@ -212,4 +292,13 @@ impl Domain for SmallDomain {
     fn size(&self) -> u64 {
         self.body.count_ones() as u64
     }
+
+    fn nth_present(&self, k: u64) -> i64 {
+        assert!(k < self.size(), "k is out of range");
+        self.start + nth_set_bit(self.body, k as u32) as i64
+    }
+
+    fn bitmap(&self) -> Option<(i64, &[u64])> {
+        Some((self.start, std::slice::from_ref(&self.body)))
+    }
 }
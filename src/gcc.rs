@@ -1,14 +1,15 @@
 use crate::alldifferent::{ACMatching, MatchingReturnValue};
 use crate::constraint::Constraint;
 use crate::events::Event;
-use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::propagator::{max_domain_size, Propagator, PropagatorControlBlock, PRIORITY_LOW};
 use crate::scc::compute_scc;
-use crate::solver::Solver;
+use crate::solver::{Solver, SolverState};
 use crate::variable::Variable;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// every value k occurs at most card[k] times, values outside of card are forbidden
 pub struct GlobalCardinalityConstraint {
     vars: Vec<Rc<RefCell<Variable>>>,
     card: HashMap<i64, i32>,
@@ -28,10 +29,9 @@ impl Constraint for GlobalCardinalityConstraint {
     fn satisfied(&self) -> bool {
         let mut card = HashMap::<i64, i32>::new();
         for v in &self.vars {
-            if !v.borrow().is_assigned() {
+            let Some(val) = v.borrow().try_value() else {
                 return false;
-            }
-            let val = v.borrow().value();
+            };
             if let Some(c) = card.get_mut(&val) {
                 *c += 1;
             } else {
@@ -58,8 +58,13 @@ impl Constraint for GlobalCardinalityConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
 }
 
+/// removes every value which is in no matching respecting the capacities,
+/// the unmatched edges between strongly connected components of the residual flow graph
 pub struct GlobalCardinalityACPropagator {
     pcb: PropagatorControlBlock,
     vars: Vec<Rc<RefCell<Variable>>>,
@@ -119,7 +124,251 @@ impl Propagator for GlobalCardinalityACPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * max_domain_size(&self.vars)
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_LOW
+    }
+}
+
+/// every value k occurs between card[k].0 and card[k].1 times, values outside of card are forbidden
+pub struct BoundedGccConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    card: HashMap<i64, (i32, i32)>,
+}
+
+impl BoundedGccConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, card: HashMap<i64, (usize, usize)>) -> Self {
+        let mut c = HashMap::new();
+        for (k, (lo, hi)) in card {
+            assert!(lo <= hi);
+            let hi = i32::try_from(hi).expect("cardinality bound does not fit in i32");
+            c.insert(k, (lo as i32, hi));
+        }
+        Self { vars, card: c }
+    }
+}
+
+impl Constraint for BoundedGccConstraint {
+    fn satisfied(&self) -> bool {
+        let mut card = HashMap::<i64, i32>::new();
+        for v in &self.vars {
+            let Some(val) = v.borrow().try_value() else {
+                return false;
+            };
+            *card.entry(val).or_insert(0) += 1;
+        }
+        if card.keys().any(|k| !self.card.contains_key(k)) {
+            return false;
+        }
+        self.card.iter().all(|(k, (lo, hi))| {
+            let c = card.get(k).cloned().unwrap_or(0);
+            *lo <= c && c <= *hi
+        })
+    }
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(BoundedGccACPropagator::new(
+            self.vars.clone(),
+            self.card.clone(),
+            solver.solver_state(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// flow based propagator for lower and upper bounds
+/// the matching respecting upper bounds is repaired along alternating paths until lower bounds hold,
+/// then edges outside of the residual graph's strongly connected components are removed
+pub struct BoundedGccACPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    card: HashMap<i64, (i32, i32)>,
+    upper: HashMap<i64, i32>,
+    // failures go here, as there may be no variables to fail through
+    solver_state: Rc<RefCell<SolverState>>,
+}
+
+impl BoundedGccACPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        card: HashMap<i64, (i32, i32)>,
+        solver_state: Rc<RefCell<SolverState>>,
+        id: usize,
+    ) -> Self {
+        let upper = card.iter().map(|(k, (_, hi))| (*k, *hi)).collect();
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            card,
+            upper,
+            solver_state,
+        }
+    }
+
+    // moves one variable to value d, taking it from a value with count above its lower bound
+    // adj[a] are the variables which can take value a, returns false if there is no such path
+    fn augment(
+        adj: &[Vec<usize>],
+        lower: &[i32],
+        mate: &mut [usize],
+        count: &mut [i32],
+        d: usize,
+    ) -> bool {
+        // parent[b] = (previous value, variable moved from b to it)
+        let mut parent = vec![None; count.len()];
+        let mut visited = vec![false; count.len()];
+        let mut q = vec![d];
+        visited[d] = true;
+        let mut qh = 0;
+        while qh < q.len() {
+            let a = q[qh];
+            qh += 1;
+            for x in adj[a].iter().cloned() {
+                let b = mate[x];
+                if visited[b] {
+                    continue;
+                }
+                visited[b] = true;
+                parent[b] = Some((a, x));
+                if count[b] > lower[b] {
+                    count[b] -= 1;
+                    count[d] += 1;
+                    let mut cur = b;
+                    while let Some((prev, x)) = parent[cur] {
+                        mate[x] = prev;
+                        cur = prev;
+                    }
+                    return true;
+                }
+                q.push(b);
+            }
+        }
+        false
+    }
+}
+
+impl Propagator for BoundedGccACPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        for v in &self.vars {
+            let forbidden: Vec<_> = v
+                .borrow()
+                .iter()
+                .filter(|x| self.upper.get(x).cloned().unwrap_or(0) == 0)
+                .collect();
+            for x in forbidden {
+                if !v.borrow_mut().remove(x) {
+                    return;
+                }
+            }
+        }
+        let n = self.vars.len();
+        let mut m = ACMatching::new(&self.vars, Some(&self.upper));
+        let Some(g) = m.matching(MatchingReturnValue::MatchingGraph) else {
+            self.solver_state.borrow_mut().fail();
+            return;
+        };
+        let k = m.vals.len();
+        // values are numbered 0, ..., k - 1 here, the matching graph has them at n, ..., n + k - 1
+        let mut mate = vec![0; n];
+        let mut count = vec![0; k];
+        for (x, mx) in mate.iter_mut().enumerate() {
+            *mx = g[x][0] - n;
+            count[*mx] += 1;
+        }
+        let mut adj = vec![Vec::new(); k];
+        for (i, val) in m.vals.iter().enumerate() {
+            for (x, v) in self.vars.iter().enumerate() {
+                if v.borrow().possible(*val) {
+                    adj[i].push(x);
+                }
+            }
+        }
+        let lower: Vec<_> = m.vals.iter().map(|v| self.card[v].0).collect();
+        // values which can not be taken by any variable are not part of the graph
+        for (val, (lo, _)) in self.card.iter() {
+            if *lo > 0 && !m.vals.contains(val) {
+                self.solver_state.borrow_mut().fail();
+                return;
+            }
+        }
+        for d in 0..k {
+            while count[d] < lower[d] {
+                if !Self::augment(&adj, &lower, &mut mate, &mut count, d) {
+                    self.solver_state.borrow_mut().fail();
+                    return;
+                }
+            }
+        }
+        // residual graph: variables, values, then a sink linking the values
+        let t = n + k;
+        let mut res = vec![Vec::new(); t + 1];
+        for (i, xs) in adj.iter().enumerate() {
+            for x in xs.iter().cloned() {
+                if mate[x] == i {
+                    res[n + i].push(x);
+                } else {
+                    res[x].push(n + i);
+                }
+            }
+            let upper = self.upper[&m.vals[i]];
+            if count[i] < upper {
+                res[n + i].push(t);
+            }
+            if count[i] > lower[i] {
+                res[t].push(n + i);
+            }
+        }
+        let scc = compute_scc(&res);
+        let mut comp_id = vec![0; res.len()];
+        for (i, comp) in scc.iter().enumerate() {
+            for v in comp.iter().cloned() {
+                comp_id[v] = i;
+            }
+        }
+        for (i, xs) in adj.iter().enumerate() {
+            for x in xs.iter().cloned() {
+                if mate[x] != i && comp_id[x] != comp_id[n + i] {
+                    self.vars[x].borrow_mut().remove(m.vals[i]);
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * max_domain_size(&self.vars)
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_LOW
+    }
 }
@@ -1,6 +1,7 @@
+use crate::alldifferent::AllDifferentConstraint;
 use crate::constraint::Constraint;
 use crate::events::Event;
-use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::propagator::{max_domain_size, Propagator, PropagatorControlBlock};
 use crate::scc::compute_scc;
 use crate::solver::Solver;
 use crate::variable::Variable;
@@ -155,18 +156,18 @@ impl TreeConstraint {
 
 impl Constraint for TreeConstraint {
     fn satisfied(&self) -> bool {
-        if !self.ntree.borrow().is_assigned() {
+        let Some(ntree) = self.ntree.borrow().try_value() else {
             return false;
-        }
-        let ntree = self.ntree.borrow().value() as usize;
+        };
+        let ntree = ntree as usize;
         let mut out = vec![Vec::new(); self.parent.len()];
         let mut trees = Vec::new();
         let mut used = vec![false; self.parent.len()];
         for i in 0..self.parent.len() {
-            if !self.parent[i].borrow().is_assigned() {
+            let Some(j) = self.parent[i].borrow().try_value() else {
                 return false;
-            }
-            let j = self.parent[i].borrow().value() as usize;
+            };
+            let j = j as usize;
             if i != j {
                 out[j].push(i);
             } else {
@@ -204,6 +205,12 @@ impl Constraint for TreeConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.ntree.clone()];
+        vars.extend(self.parent.iter().cloned());
+        vars
+    }
 }
 
 pub struct TreePropagator {
@@ -279,10 +286,7 @@ impl Propagator for TreePropagator {
                 mintree += 1;
             }
         }
-        if !self.ntree.borrow_mut().set_lb(mintree) {
-            return;
-        }
-        if !self.ntree.borrow_mut().set_ub(maxtree) {
+        if !self.ntree.borrow_mut().tighten(mintree, maxtree) {
             return;
         }
         let mut dt = DominatorTree::new(ext_gr);
@@ -319,7 +323,150 @@ impl Propagator for TreePropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.parent.len() * self.parent.len()
+    }
+}
+
+/// succ[i] = j means that the circuit goes from i to j,
+/// the circuit visits every vertex exactly once, so self-loops are only possible for a single vertex
+pub struct CircuitConstraint {
+    succ: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl CircuitConstraint {
+    pub fn new(succ: Vec<Rc<RefCell<Variable>>>) -> Self {
+        Self { succ }
+    }
+}
+
+impl Constraint for CircuitConstraint {
+    fn satisfied(&self) -> bool {
+        let n = self.succ.len();
+        let mut used = vec![false; n];
+        let mut v = 0;
+        for _ in 0..n {
+            let Some(u) = self.succ[v].borrow().try_value() else {
+                return false;
+            };
+            if u < 0 || u as usize >= n || used[u as usize] {
+                return false;
+            }
+            used[u as usize] = true;
+            v = u as usize;
+        }
+        true
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        // successors form a permutation
+        AllDifferentConstraint::new(self.succ.clone()).create_propagators(solver);
+        let p = Rc::new(RefCell::new(CircuitPropagator::new(
+            self.succ.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.succ.clone()
+    }
+}
+
+/// removes edges closing a cycle shorter than n at the end of a chain of assigned successors,
+/// and fails if the graph of possible successors is not strongly connected
+pub struct CircuitPropagator {
+    pcb: PropagatorControlBlock,
+    succ: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl CircuitPropagator {
+    pub fn new(succ: Vec<Rc<RefCell<Variable>>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            succ,
+        }
+    }
+}
+
+impl Propagator for CircuitPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.succ {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.succ.len();
+        if n == 0 {
+            return;
+        }
+        for (v, var) in self.succ.iter().enumerate() {
+            let mut var = var.borrow_mut();
+            if !var.tighten(0, n as i64 - 1) {
+                return;
+            }
+            if n > 1 && !var.remove(v as i64) {
+                return;
+            }
+        }
+        let next: Vec<Option<usize>> = self
+            .succ
+            .iter()
+            .map(|v| v.borrow().try_value().map(|x| x as usize))
+            .collect();
+        let mut has_pred = vec![false; n];
+        for u in next.iter().flatten() {
+            has_pred[*u] = true;
+        }
+        // every chain of assigned successors starts at a vertex without assigned predecessor,
+        // its end must not go back to its start unless the chain covers everything
+        for start in (0..n).filter(|v| !has_pred[*v]) {
+            let mut end = start;
+            let mut len = 1;
+            while let Some(u) = next[end] {
+                if len == n {
+                    // two vertices with the same successor
+                    self.succ[0].borrow().fail();
+                    return;
+                }
+                end = u;
+                len += 1;
+            }
+            if len < n && !self.succ[end].borrow_mut().remove(start as i64) {
+                return;
+            }
+        }
+        // closed cycles of assigned successors shorter than n are found here
+        let gr: Vec<Vec<usize>> = self
+            .succ
+            .iter()
+            .map(|v| v.borrow().iter().map(|x| x as usize).collect())
+            .collect();
+        if compute_scc(&gr).len() > 1 {
+            self.succ[0].borrow().fail();
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.succ.len() * max_domain_size(&self.succ)
+    }
 }
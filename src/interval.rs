@@ -0,0 +1,190 @@
+use crate::bitset::BitsetDomain;
+use crate::domain::{Domain, DomainState};
+use crate::solver::SolverState;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// domain stored as its bounds only, so its size does not depend on the width of the range
+/// removing a value strictly between the bounds turns it into a BitsetDomain over the current bounds,
+/// which is as expensive as a bitset from the start, so it suits variables pruned by bounds propagators
+/// the bitset is dropped again when the search backtracks past the removal
+pub struct IntervalDomain {
+    solver_state: Rc<RefCell<SolverState>>,
+    lb: i64,
+    ub: i64,
+    // number of open checkpoints
+    depth: usize,
+    // (depth, bounds before the first change at that depth)
+    trail: Vec<(usize, i64, i64)>,
+    // bitset holding the domain since the checkpoint at the given depth
+    holes: Option<(usize, BitsetDomain)>,
+}
+
+impl IntervalDomain {
+    fn save(&mut self) {
+        if self.trail.last().is_none_or(|(d, _, _)| *d != self.depth) {
+            self.trail.push((self.depth, self.lb, self.ub));
+        }
+    }
+
+    fn fail(&self) -> DomainState {
+        self.solver_state.borrow_mut().fail();
+        DomainState::Failed
+    }
+}
+
+impl Domain for IntervalDomain {
+    fn new(solver_state: Rc<RefCell<SolverState>>, lb: i64, ub: i64) -> Self {
+        Self {
+            solver_state,
+            lb,
+            ub,
+            depth: 0,
+            trail: Vec::new(),
+            holes: None,
+        }
+    }
+
+    fn assign(&mut self, x: i64) -> DomainState {
+        if let Some((_, d)) = &mut self.holes {
+            return d.assign(x);
+        }
+        self.tighten(x, x)
+    }
+
+    fn is_assigned(&self) -> bool {
+        match &self.holes {
+            Some((_, d)) => d.is_assigned(),
+            None => self.lb == self.ub,
+        }
+    }
+
+    fn remove(&mut self, x: i64) -> DomainState {
+        if let Some((_, d)) = &mut self.holes {
+            return d.remove(x);
+        }
+        if x == self.lb {
+            return self.set_lb(x + 1);
+        }
+        if x == self.ub {
+            return self.set_ub(x - 1);
+        }
+        if x < self.lb || x > self.ub {
+            return DomainState::Same;
+        }
+        let mut d = BitsetDomain::new(self.solver_state.clone(), self.lb, self.ub);
+        let state = d.remove(x);
+        self.holes = Some((self.depth, d));
+        state
+    }
+
+    fn possible(&self, x: i64) -> bool {
+        match &self.holes {
+            Some((_, d)) => d.possible(x),
+            None => self.lb <= x && x <= self.ub,
+        }
+    }
+
+    fn get_lb(&self) -> i64 {
+        match &self.holes {
+            Some((_, d)) => d.get_lb(),
+            None => self.lb,
+        }
+    }
+
+    fn get_ub(&self) -> i64 {
+        match &self.holes {
+            Some((_, d)) => d.get_ub(),
+            None => self.ub,
+        }
+    }
+
+    fn set_lb(&mut self, x: i64) -> DomainState {
+        if let Some((_, d)) = &mut self.holes {
+            return d.set_lb(x);
+        }
+        if x <= self.lb {
+            return DomainState::Same;
+        }
+        if x > self.ub {
+            return self.fail();
+        }
+        self.save();
+        self.lb = x;
+        DomainState::Modified
+    }
+
+    fn set_ub(&mut self, x: i64) -> DomainState {
+        if let Some((_, d)) = &mut self.holes {
+            return d.set_ub(x);
+        }
+        if x >= self.ub {
+            return DomainState::Same;
+        }
+        if x < self.lb {
+            return self.fail();
+        }
+        self.save();
+        self.ub = x;
+        DomainState::Modified
+    }
+
+    fn checkpoint(&mut self) {
+        self.depth += 1;
+        if let Some((_, d)) = &mut self.holes {
+            d.checkpoint();
+        }
+    }
+
+    fn rollback(&mut self) {
+        assert!(self.depth > 0, "rollback without checkpoint");
+        match &mut self.holes {
+            Some((created, _)) if *created == self.depth => self.holes = None,
+            Some((_, d)) => d.rollback(),
+            None => {}
+        }
+        if self.trail.last().is_some_and(|(d, _, _)| *d == self.depth) {
+            let (_, lb, ub) = self.trail.pop().unwrap();
+            self.lb = lb;
+            self.ub = ub;
+        }
+        self.depth -= 1;
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = i64> + '_> {
+        match &self.holes {
+            Some((_, d)) => d.iter(),
+            None => Box::new(self.lb..=self.ub),
+        }
+    }
+
+    fn size(&self) -> u64 {
+        match &self.holes {
+            Some((_, d)) => d.size(),
+            // the full i64 range has 2^64 values
+            None => u64::try_from(self.ub as i128 - self.lb as i128 + 1).unwrap_or(u64::MAX),
+        }
+    }
+
+    fn nth_present(&self, k: u64) -> i64 {
+        match &self.holes {
+            Some((_, d)) => d.nth_present(k),
+            None => {
+                assert!(k < self.size(), "k is out of range");
+                self.lb + k as i64
+            }
+        }
+    }
+
+    fn bitmap(&self) -> Option<(i64, &[u64])> {
+        self.holes.as_ref().and_then(|(_, d)| d.bitmap())
+    }
+
+    fn holes(&self) -> Vec<(i64, i64)> {
+        match &self.holes {
+            Some((_, d)) => d.holes(),
+            None => Vec::new(),
+        }
+    }
+}
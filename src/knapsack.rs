@@ -0,0 +1,232 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// x[i] are 0/1, sum weights[i] * x[i] <= capacity and sum profits[i] * x[i] = profit
+pub struct KnapsackConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    weights: Vec<i64>,
+    profits: Vec<i64>,
+    capacity: i64,
+    profit: Rc<RefCell<Variable>>,
+}
+
+impl KnapsackConstraint {
+    /// weights and profits have to be non-negative
+    pub fn new(
+        x: Vec<Rc<RefCell<Variable>>>,
+        weights: Vec<i64>,
+        profits: Vec<i64>,
+        capacity: i64,
+        profit: Rc<RefCell<Variable>>,
+    ) -> Self {
+        assert!(x.len() == weights.len() && x.len() == profits.len());
+        assert!(weights.iter().chain(profits.iter()).all(|v| *v >= 0));
+        Self {
+            x,
+            weights,
+            profits,
+            capacity,
+            profit,
+        }
+    }
+}
+
+impl Constraint for KnapsackConstraint {
+    fn satisfied(&self) -> bool {
+        let Some(profit) = self.profit.borrow().try_value() else {
+            return false;
+        };
+        let mut weight_sum = 0;
+        let mut profit_sum = 0;
+        for (i, x) in self.x.iter().enumerate() {
+            match x.borrow().try_value() {
+                Some(0) => {}
+                Some(1) => {
+                    weight_sum += self.weights[i];
+                    profit_sum += self.profits[i];
+                }
+                _ => return false,
+            }
+        }
+        weight_sum <= self.capacity && profit_sum == profit
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(KnapsackPropagator::new(
+            self.x.clone(),
+            self.weights.clone(),
+            self.profits.clone(),
+            self.capacity,
+            self.profit.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.x.clone();
+        vars.push(self.profit.clone());
+        vars
+    }
+}
+
+/// bounds the profit by the linear relaxation (Dantzig bound) of the free items,
+/// and fixes every free item whose inclusion or exclusion makes that bound drop below the profit's lower bound
+pub struct KnapsackPropagator {
+    pcb: PropagatorControlBlock,
+    x: Vec<Rc<RefCell<Variable>>>,
+    weights: Vec<i64>,
+    profits: Vec<i64>,
+    capacity: i64,
+    profit: Rc<RefCell<Variable>>,
+    // items by decreasing profit per weight, the order of the relaxation
+    order: Vec<usize>,
+}
+
+impl KnapsackPropagator {
+    pub fn new(
+        x: Vec<Rc<RefCell<Variable>>>,
+        weights: Vec<i64>,
+        profits: Vec<i64>,
+        capacity: i64,
+        profit: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        let mut order: Vec<usize> = (0..x.len()).collect();
+        // p[i] / w[i] > p[j] / w[j], items of zero weight first
+        order.sort_by(|i, j| {
+            let a = profits[*i] as i128 * weights[*j] as i128;
+            let b = profits[*j] as i128 * weights[*i] as i128;
+            match b.cmp(&a) {
+                Ordering::Equal => weights[*i].cmp(&weights[*j]),
+                ord => ord,
+            }
+        });
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            weights,
+            profits,
+            capacity,
+            profit,
+            order,
+        }
+    }
+
+    // largest profit of the relaxation over free items except skip, with capacity room
+    fn relaxation(&self, free: &[bool], skip: usize, mut room: i64) -> i64 {
+        let mut bound = 0;
+        for i in self.order.iter().cloned() {
+            if !free[i] || i == skip {
+                continue;
+            }
+            if self.weights[i] <= room {
+                room -= self.weights[i];
+                bound += self.profits[i];
+            } else {
+                // profits are integral, so the fractional part can be dropped
+                bound +=
+                    ((self.profits[i] as i128 * room as i128) / self.weights[i] as i128) as i64;
+                break;
+            }
+        }
+        bound
+    }
+}
+
+impl Propagator for KnapsackPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for x in &self.x {
+            x.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+        self.profit
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        let n = self.x.len();
+        let mut free = vec![false; n];
+        let mut room = self.capacity;
+        let mut fixed_profit = 0;
+        for (i, x) in self.x.iter().enumerate() {
+            if !x.borrow_mut().tighten(0, 1) {
+                return;
+            }
+            match x.borrow().try_value() {
+                Some(1) => {
+                    room -= self.weights[i];
+                    fixed_profit += self.profits[i];
+                }
+                Some(_) => {}
+                None => free[i] = true,
+            }
+        }
+        if room < 0 {
+            self.profit.borrow().fail();
+            return;
+        }
+        for (i, is_free) in free.iter_mut().enumerate() {
+            if *is_free && self.weights[i] > room {
+                if !self.x[i].borrow_mut().assign(0) {
+                    return;
+                }
+                *is_free = false;
+            }
+        }
+        let best = fixed_profit + self.relaxation(&free, n, room);
+        if !self.profit.borrow_mut().tighten(fixed_profit, best) {
+            return;
+        }
+        // reduced cost filtering against the lower bound of the profit
+        let lb = self.profit.borrow().get_lb();
+        for i in 0..n {
+            if !free[i] {
+                continue;
+            }
+            // items fixed to 1 above could leave no room for item i
+            let with_i = (self.weights[i] <= room).then(|| {
+                fixed_profit + self.profits[i] + self.relaxation(&free, i, room - self.weights[i])
+            });
+            let without_i = fixed_profit + self.relaxation(&free, i, room);
+            let forced = match (with_i.is_none_or(|v| v < lb), without_i < lb) {
+                (true, true) => {
+                    self.profit.borrow().fail();
+                    return;
+                }
+                (true, false) => 0,
+                (false, true) => 1,
+                (false, false) => continue,
+            };
+            if !self.x[i].borrow_mut().assign(forced) {
+                return;
+            }
+            // the other items are checked against the updated assignment
+            free[i] = false;
+            if forced == 1 {
+                room -= self.weights[i];
+                fixed_profit += self.profits[i];
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.len() * self.x.len()
+    }
+}
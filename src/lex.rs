@@ -0,0 +1,346 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock, PRIORITY_HIGH};
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// x < y in lexicographic order
+pub struct LexLessConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl LexLessConstraint {
+    /// panics if the vectors are empty, as two empty tuples are never strictly ordered
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, y: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert_eq!(x.len(), y.len());
+        assert!(!x.is_empty());
+        Self { x, y }
+    }
+}
+
+/// x <= y in lexicographic order
+pub struct LexLessEqConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl LexLessEqConstraint {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, y: Vec<Rc<RefCell<Variable>>>) -> Self {
+        assert_eq!(x.len(), y.len());
+        Self { x, y }
+    }
+}
+
+// comparison of the assigned tuples, None if something is unassigned
+fn compare_assigned(x: &[Rc<RefCell<Variable>>], y: &[Rc<RefCell<Variable>>]) -> Option<Ordering> {
+    let mut xv = Vec::with_capacity(x.len());
+    let mut yv = Vec::with_capacity(y.len());
+    for (a, b) in x.iter().zip(y.iter()) {
+        xv.push(a.borrow().try_value()?);
+        yv.push(b.borrow().try_value()?);
+    }
+    Some(xv.cmp(&yv))
+}
+
+fn create_lex_propagator(
+    solver: &mut Solver,
+    x: &[Rc<RefCell<Variable>>],
+    y: &[Rc<RefCell<Variable>>],
+    strict: bool,
+) {
+    let p = Rc::new(RefCell::new(LexPropagator::new(
+        x.to_vec(),
+        y.to_vec(),
+        strict,
+        solver.new_propagator_id(),
+    )));
+    solver.add_propagator(p.clone());
+    p.borrow().listen(p.clone());
+}
+
+impl Constraint for LexLessConstraint {
+    fn satisfied(&self) -> bool {
+        compare_assigned(&self.x, &self.y) == Some(Ordering::Less)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        create_lex_propagator(solver, &self.x, &self.y, true);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.x.iter().chain(self.y.iter()).cloned().collect()
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        Some(Rc::new(RefCell::new(LexLessEqConstraint::new(
+            self.y.clone(),
+            self.x.clone(),
+        ))))
+    }
+}
+
+impl Constraint for LexLessEqConstraint {
+    fn satisfied(&self) -> bool {
+        matches!(
+            compare_assigned(&self.x, &self.y),
+            Some(Ordering::Less | Ordering::Equal)
+        )
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        create_lex_propagator(solver, &self.x, &self.y, false);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.x.iter().chain(self.y.iter()).cloned().collect()
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        // an empty y is never strictly smaller
+        if self.x.is_empty() {
+            return None;
+        }
+        Some(Rc::new(RefCell::new(LexLessConstraint::new(
+            self.y.clone(),
+            self.x.clone(),
+        ))))
+    }
+}
+
+/// propagator for x < y (strict) or x <= y in lexicographic order
+/// prunes at the first position where x and y are not forced to be equal
+pub struct LexPropagator {
+    pcb: PropagatorControlBlock,
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+    strict: bool,
+}
+
+impl LexPropagator {
+    pub fn new(
+        x: Vec<Rc<RefCell<Variable>>>,
+        y: Vec<Rc<RefCell<Variable>>>,
+        strict: bool,
+        id: usize,
+    ) -> Self {
+        assert_eq!(x.len(), y.len());
+        assert!(!strict || !x.is_empty());
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+            strict,
+        }
+    }
+
+    // whether x[from..] can still be ordered before y[from..], comparing smallest x with largest y
+    fn suffix_feasible(&self, from: usize) -> bool {
+        for i in from..self.x.len() {
+            let lb = self.x[i].borrow().get_lb();
+            let ub = self.y[i].borrow().get_ub();
+            match lb.cmp(&ub) {
+                Ordering::Less => {
+                    return true;
+                }
+                Ordering::Greater => {
+                    return false;
+                }
+                Ordering::Equal => {}
+            }
+        }
+        !self.strict
+    }
+}
+
+impl Propagator for LexPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in self.x.iter().chain(self.y.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.x.len();
+        let mut alpha = 0;
+        while alpha < n {
+            let (x, y) = (&self.x[alpha], &self.y[alpha]);
+            let fixed_equal = {
+                let (x, y) = (x.borrow(), y.borrow());
+                x.is_assigned() && y.is_assigned() && x.get_lb() == y.get_lb()
+            };
+            if fixed_equal {
+                alpha += 1;
+                continue;
+            }
+            // x[alpha] == y[alpha] is allowed only if the rest can be ordered
+            let gap = if self.suffix_feasible(alpha + 1) {
+                0
+            } else {
+                1
+            };
+            let y_ub = y.borrow().get_ub();
+            if !x.borrow_mut().set_ub(y_ub - gap) {
+                return;
+            }
+            let x_lb = x.borrow().get_lb();
+            if !y.borrow_mut().set_lb(x_lb + gap) {
+                return;
+            }
+            let still_equal = {
+                let (x, y) = (x.borrow(), y.borrow());
+                x.is_assigned() && y.is_assigned() && x.get_lb() == y.get_lb()
+            };
+            if !still_equal {
+                return;
+            }
+            alpha += 1;
+        }
+        // prefixes are equal everywhere
+        if self.strict {
+            self.x[0].borrow().fail();
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_HIGH
+    }
+}
+
+/// if t occurs in x, then s occurs in x before its first occurrence
+pub struct ValuePrecedenceConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    s: i64,
+    t: i64,
+}
+
+impl ValuePrecedenceConstraint {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, s: i64, t: i64) -> Self {
+        assert_ne!(s, t);
+        Self { x, s, t }
+    }
+}
+
+impl Constraint for ValuePrecedenceConstraint {
+    fn satisfied(&self) -> bool {
+        let Some(values) = self
+            .x
+            .iter()
+            .map(|v| v.borrow().try_value())
+            .collect::<Option<Vec<i64>>>()
+        else {
+            return false;
+        };
+        match values.iter().position(|v| *v == self.t) {
+            Some(first_t) => values[..first_t].contains(&self.s),
+            None => true,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(ValuePrecedencePropagator::new(
+            self.x.clone(),
+            self.s,
+            self.t,
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.x.clone()
+    }
+}
+
+/// t is removed up to the earliest position still able to hold s,
+/// and s is forced there when it is the only such position before a fixed t
+pub struct ValuePrecedencePropagator {
+    pcb: PropagatorControlBlock,
+    x: Vec<Rc<RefCell<Variable>>>,
+    s: i64,
+    t: i64,
+}
+
+impl ValuePrecedencePropagator {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, s: i64, t: i64, id: usize) -> Self {
+        assert_ne!(s, t);
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            s,
+            t,
+        }
+    }
+}
+
+impl Propagator for ValuePrecedencePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.x {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.x.len();
+        let alpha = self
+            .x
+            .iter()
+            .position(|v| v.borrow().possible(self.s))
+            .unwrap_or(n);
+        // no s can precede t at positions up to alpha
+        for v in self.x.iter().take(alpha + 1) {
+            if !v.borrow_mut().remove(self.t) {
+                return;
+            }
+        }
+        let Some(gamma) = self
+            .x
+            .iter()
+            .position(|v| v.borrow().try_value() == Some(self.t))
+        else {
+            return;
+        };
+        let beta = self.x[alpha + 1..gamma]
+            .iter()
+            .position(|v| v.borrow().possible(self.s));
+        if beta.is_none() {
+            self.x[alpha].borrow_mut().assign(self.s);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.len()
+    }
+}
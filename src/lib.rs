@@ -2,17 +2,29 @@ pub mod alldifferent;
 pub mod arithmetic;
 pub mod binpacking;
 pub mod bitset;
+pub mod channel;
+pub mod config;
 pub mod constraint;
+pub mod count;
 pub mod domain;
 pub mod events;
 pub mod gcc;
 pub mod graph;
+pub mod interval;
+pub mod knapsack;
+pub mod lex;
 pub mod linear;
 pub mod logic;
 pub mod objective_function;
 pub mod propagator;
+pub mod regular;
+pub mod reify;
 pub mod scc;
+pub mod scheduling;
 pub mod solver;
+pub mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod value_selector;
 pub mod variable;
 pub mod variable_selector;
@@ -1,13 +1,15 @@
 use crate::constraint::Constraint;
 use crate::events::Event;
-use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::propagator::{
+    Explanation, Propagator, PropagatorControlBlock, Reason, PRIORITY_HIGH,
+};
 use crate::solver::Solver;
 use crate::variable::Variable;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 // assuming q > 0
-fn floor_div(p: i64, q: i64) -> i64 {
+pub(crate) fn floor_div(p: i64, q: i64) -> i64 {
     if p > 0 {
         p/q
     } else {
@@ -16,7 +18,7 @@ fn floor_div(p: i64, q: i64) -> i64 {
 }
 
 // assuming q > 0
-fn ceil_div(p: i64, q: i64) -> i64 {
+pub(crate) fn ceil_div(p: i64, q: i64) -> i64 {
     if p > 0 {
         (p + q - 1)/q
     } else {
@@ -46,10 +48,10 @@ impl Constraint for LinearInequalityConstraint {
     fn satisfied(&self) -> bool {
         let mut sum = 0;
         for i in 0..self.x.len() {
-            if !self.x[i].borrow().is_assigned() {
+            let Some(x) = self.x[i].borrow().try_value() else {
                 return false;
-            }
-            sum += self.x[i].borrow().value() * self.a[i];
+            };
+            sum += x * self.a[i];
         }
         sum <= self.b
     }
@@ -64,17 +66,102 @@ impl Constraint for LinearInequalityConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.x.clone()
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        // sum x[i] * -a[i] <= -b - 1
+        Some(Rc::new(RefCell::new(LinearInequalityConstraint::new(
+            self.x.clone(),
+            self.a.iter().map(|a| -a).collect(),
+            -self.b - 1,
+        ))))
+    }
+}
+
+// sum x[i] * a[i] <= y
+pub struct LinearLeVarConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    a: Vec<i64>,
+    y: Rc<RefCell<Variable>>,
+}
+
+impl LinearLeVarConstraint {
+    pub fn new(x: Vec<Rc<RefCell<Variable>>>, a: Vec<i64>, y: Rc<RefCell<Variable>>) -> Self {
+        assert!(x.len() == a.len());
+        Self { x, a, y }
+    }
+}
+
+impl Constraint for LinearLeVarConstraint {
+    fn satisfied(&self) -> bool {
+        let Some(y) = self.y.borrow().try_value() else {
+            return false;
+        };
+        let mut sum = 0;
+        for i in 0..self.x.len() {
+            let Some(x) = self.x[i].borrow().try_value() else {
+                return false;
+            };
+            sum += x * self.a[i];
+        }
+        sum <= y
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        // y is moved to the left side with coefficient -1, so its lower bound is propagated too
+        let mut x = self.x.clone();
+        let mut a = self.a.clone();
+        x.push(self.y.clone());
+        a.push(-1);
+        let p = Rc::new(RefCell::new(LinearInequalityPropagator::new(
+            x,
+            a,
+            0,
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = self.x.clone();
+        vars.push(self.y.clone());
+        vars
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        // y - sum x[i] * a[i] <= -1
+        let mut x = self.x.clone();
+        let mut a: Vec<i64> = self.a.iter().map(|a| -a).collect();
+        x.push(self.y.clone());
+        a.push(1);
+        Some(Rc::new(RefCell::new(LinearInequalityConstraint::new(x, a, -1))))
+    }
 }
 
 pub struct LinearInequalityPropagator {
     pcb: PropagatorControlBlock,
     x: Vec<Rc<RefCell<Variable>>>,
     a: Vec<i64>,
-    b: i64,
+    b: Rc<Cell<i64>>,
 }
 
 impl LinearInequalityPropagator {
     pub fn new(x: Vec<Rc<RefCell<Variable>>>, a: Vec<i64>, b: i64, id: usize) -> Self {
+        Self::with_shared_bound(x, a, Rc::new(Cell::new(b)), id)
+    }
+
+    /// b is read at every call, so it can be changed between them, i64::MAX turns the propagator off
+    /// the propagator is not woken up by a change of b, it has to be queued by whoever changes it
+    pub fn with_shared_bound(
+        x: Vec<Rc<RefCell<Variable>>>,
+        a: Vec<i64>,
+        b: Rc<Cell<i64>>,
+        id: usize,
+    ) -> Self {
         Self {
             pcb: PropagatorControlBlock::new(id),
             x,
@@ -98,6 +185,10 @@ impl Propagator for LinearInequalityPropagator {
     }
 
     fn propagate(&mut self) {
+        let b = self.b.get();
+        if b == i64::MAX {
+            return;
+        }
         let mut lower_sum = 0;
         for i in 0..self.x.len() {
             let x = self.x[i].borrow();
@@ -107,18 +198,47 @@ impl Propagator for LinearInequalityPropagator {
                 lower_sum += x.get_ub() * self.a[i];
             }
         }
+        if lower_sum > b {
+            // catches the case of zero coefficients only, where nothing is pruned below
+            if let Some(x) = self.x.first() {
+                x.borrow().fail();
+            }
+            return;
+        }
         for i in 0..self.x.len() {
             let mut x = self.x[i].borrow_mut();
+            if self.a[i] == 0 {
+                continue;
+            }
             if self.a[i] > 0 {
-                let up = self.b - lower_sum + x.get_lb() * self.a[i];
+                let up = b - lower_sum + x.get_lb() * self.a[i];
                 x.set_ub(floor_div(up, self.a[i]));
             } else {
-                let down = -self.b + lower_sum - x.get_ub() * self.a[i];
+                let down = -b + lower_sum - x.get_ub() * self.a[i];
                 x.set_lb(ceil_div(down, -self.a[i]));
             }
         }
     }
 
+    fn explain(&self, var: Rc<RefCell<Variable>>, val: i64) -> Explanation {
+        let Some(j) = self.x.iter().position(|x| Rc::ptr_eq(x, &var)) else {
+            return vec![(var, val, Reason::Decision)];
+        };
+        // bounds of the other terms are what makes a[j] * val exceed b
+        let mut ans = Vec::with_capacity(self.x.len() - 1);
+        for (i, x) in self.x.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if self.a[i] > 0 {
+                ans.push((x.clone(), x.borrow().get_lb(), Reason::LowerBound));
+            } else {
+                ans.push((x.clone(), x.borrow().get_ub(), Reason::UpperBound));
+            }
+        }
+        ans
+    }
+
     fn get_cb(&self) -> &PropagatorControlBlock {
         &self.pcb
     }
@@ -127,7 +247,15 @@ impl Propagator for LinearInequalityPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.len()
+    }
+
+    fn priority(&self) -> u8 {
+        PRIORITY_HIGH
+    }
 }
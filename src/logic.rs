@@ -2,7 +2,7 @@ use crate::constraint::Constraint;
 use crate::events::Event;
 use crate::propagator::{Propagator, PropagatorControlBlock};
 use crate::solver::Solver;
-use crate::variable::Variable;
+use crate::variable::{BoolVar, Variable};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -16,22 +16,25 @@ impl AndConstraint {
     pub fn new(result: Rc<RefCell<Variable>>, vars: Vec<Rc<RefCell<Variable>>>) -> Self {
         Self { result, vars }
     }
+    pub fn from_bools(result: BoolVar, vars: Vec<BoolVar>) -> Self {
+        Self::new(result.into(), vars.into_iter().map(|v| v.into()).collect())
+    }
 }
 
 impl Constraint for AndConstraint {
     fn satisfied(&self) -> bool {
-        let result = match self.result.borrow().is_assigned() {
-            true => self.result.borrow().value(),
-            false => {
-                return false;
-            }
+        let Some(result) = self.result.borrow().try_value() else {
+            return false;
         };
         for v in &self.vars {
-            if !v.borrow().is_assigned() {
-                return false;
-            }
-            if v.borrow().value() == 0 {
-                return result == 0;
+            match v.borrow().try_value() {
+                None => {
+                    return false;
+                }
+                Some(0) => {
+                    return result == 0;
+                }
+                _ => {}
             }
         }
         result != 0
@@ -46,6 +49,12 @@ impl Constraint for AndConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.result.clone()];
+        vars.extend(self.vars.iter().cloned());
+        vars
+    }
 }
 
 pub struct AndPropagator {
@@ -76,8 +85,8 @@ impl Propagator for AndPropagator {
     }
 
     fn propagate(&mut self) {
-        if self.result.borrow().is_assigned() {
-            let result = self.result.borrow().value();
+        let result = self.result.borrow().try_value();
+        if let Some(result) = result {
             if result == 1 {
                 for v in &self.vars {
                     v.borrow_mut().assign(1);
@@ -86,12 +95,10 @@ impl Propagator for AndPropagator {
                 let mut ones = 0;
                 let mut unknown = 0;
                 for v in &self.vars {
-                    if v.borrow().is_assigned() {
-                        if v.borrow().value() == 1 {
-                            ones += 1;
-                        }
-                    } else {
-                        unknown += 1;
+                    match v.borrow().try_value() {
+                        Some(1) => ones += 1,
+                        Some(_) => {}
+                        None => unknown += 1,
                     }
                 }
                 if ones == self.vars.len() {
@@ -134,9 +141,13 @@ impl Propagator for AndPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() + 1
+    }
 }
 
 // result = vars[0] OR vars[1] OR ... OR vars[vars.len() - 1]
@@ -149,22 +160,25 @@ impl OrConstraint {
     pub fn new(result: Rc<RefCell<Variable>>, vars: Vec<Rc<RefCell<Variable>>>) -> Self {
         Self { result, vars }
     }
+    pub fn from_bools(result: BoolVar, vars: Vec<BoolVar>) -> Self {
+        Self::new(result.into(), vars.into_iter().map(|v| v.into()).collect())
+    }
 }
 
 impl Constraint for OrConstraint {
     fn satisfied(&self) -> bool {
-        let result = match self.result.borrow().is_assigned() {
-            true => self.result.borrow().value(),
-            false => {
-                return false;
-            }
+        let Some(result) = self.result.borrow().try_value() else {
+            return false;
         };
         for v in &self.vars {
-            if !v.borrow().is_assigned() {
-                return false;
-            }
-            if v.borrow().value() == 1 {
-                return result != 0;
+            match v.borrow().try_value() {
+                None => {
+                    return false;
+                }
+                Some(1) => {
+                    return result != 0;
+                }
+                _ => {}
             }
         }
         result == 0
@@ -179,20 +193,72 @@ impl Constraint for OrConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.result.clone()];
+        vars.extend(self.vars.iter().cloned());
+        vars
+    }
 }
 
+/// once result is 1, only two watched variables which can still be 1 are listened to
+/// watches stay valid on backtracking, so they are not restored
+/// after backtracking above the assignment of result, other variables are listened to again from the next call
 pub struct OrPropagator {
     pcb: PropagatorControlBlock,
     result: Rc<RefCell<Variable>>,
     vars: Vec<Rc<RefCell<Variable>>>,
+    watches: [usize; 2],
 }
 
 impl OrPropagator {
     pub fn new(result: Rc<RefCell<Variable>>, vars: Vec<Rc<RefCell<Variable>>>, id: usize) -> Self {
+        let watches = [0, usize::min(1, vars.len().saturating_sub(1))];
         Self {
             pcb: PropagatorControlBlock::new(id),
             result,
             vars,
+            watches,
+        }
+    }
+
+    fn clause_mode(&self) -> bool {
+        self.result.borrow().try_value() == Some(1)
+    }
+
+    // result is 1, at least one of vars has to be 1
+    fn propagate_clause(&mut self) {
+        let n = self.vars.len();
+        if n == 0 {
+            self.result.borrow().fail();
+            return;
+        }
+        for k in 0..2 {
+            let w = self.watches[k];
+            let other = self.watches[1 - k];
+            if w != other && self.vars[w].borrow().possible(1) {
+                continue;
+            }
+            let replacement = (1..=n)
+                .map(|d| (w + d) % n)
+                .find(|i| *i != other && self.vars[*i].borrow().possible(1));
+            if let Some(i) = replacement {
+                self.watches[k] = i;
+            }
+        }
+        // a watch without replacement means that all variables except the other watch are 0
+        let [a, b] = self.watches;
+        let a_alive = self.vars[a].borrow().possible(1);
+        let b_alive = a != b && self.vars[b].borrow().possible(1);
+        match (a_alive, b_alive) {
+            (false, false) => self.result.borrow().fail(),
+            (true, false) => {
+                self.vars[a].borrow_mut().assign(1);
+            }
+            (false, true) => {
+                self.vars[b].borrow_mut().assign(1);
+            }
+            (true, true) => {}
         }
     }
 }
@@ -202,6 +268,16 @@ impl Propagator for OrPropagator {
         self.result
             .borrow_mut()
             .add_listener(self_pointer.clone(), Event::Modified);
+        if self.clause_mode() {
+            // a watched variable losing value 1 is the only event that matters
+            for w in self.watches {
+                if let Some(v) = self.vars.get(w) {
+                    v.borrow_mut()
+                        .add_listener(self_pointer.clone(), Event::UpperBound);
+                }
+            }
+            return;
+        }
         for v in &self.vars {
             v.borrow_mut()
                 .add_listener(self_pointer.clone(), Event::Modified);
@@ -209,26 +285,10 @@ impl Propagator for OrPropagator {
     }
 
     fn propagate(&mut self) {
-        if self.result.borrow().is_assigned() {
-            let result = self.result.borrow().value();
+        let result = self.result.borrow().try_value();
+        if let Some(result) = result {
             if result == 1 {
-                let mut ones = 0;
-                for v in &self.vars {
-                    if v.borrow().possible(1) {
-                        ones += 1;
-                    }
-                }
-                if ones == 0 {
-                    self.result.borrow_mut().fail();
-                    return;
-                }
-                if ones == 1 {
-                    for v in &self.vars {
-                        if v.borrow().possible(1) {
-                            v.borrow_mut().assign(1);
-                        }
-                    }
-                }
+                self.propagate_clause();
             } else {
                 for v in &self.vars {
                     v.borrow_mut().assign(0);
@@ -262,9 +322,13 @@ impl Propagator for OrPropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() + 1
+    }
 }
 
 // x = not y
@@ -277,14 +341,16 @@ impl NegateConstraint {
     pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>) -> Self {
         Self { x, y }
     }
+    pub fn from_bools(x: BoolVar, y: BoolVar) -> Self {
+        Self::new(x.into(), y.into())
+    }
 }
 
 impl Constraint for NegateConstraint {
     fn satisfied(&self) -> bool {
-        if !self.x.borrow().is_assigned() || !self.y.borrow().is_assigned() {
-            false
-        } else {
-            self.x.borrow().value() != self.y.borrow().value()
+        match (self.x.borrow().try_value(), self.y.borrow().try_value()) {
+            (Some(x), Some(y)) => x != y,
+            _ => false,
         }
     }
 
@@ -297,6 +363,10 @@ impl Constraint for NegateConstraint {
         solver.add_propagator(p.clone());
         p.borrow().listen(p.clone());
     }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
 }
 
 pub struct NegatePropagator {
@@ -346,7 +416,234 @@ impl Propagator for NegatePropagator {
         &mut self.pcb
     }
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         true
     }
 }
+
+// b = (x >> bit) & 1
+pub struct BitConstraint {
+    x: Rc<RefCell<Variable>>,
+    bit: u32,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl BitConstraint {
+    pub fn new(x: Rc<RefCell<Variable>>, bit: u32, b: Rc<RefCell<Variable>>) -> Self {
+        assert!(bit < 64, "bit index {} is out of range", bit);
+        Self { x, bit, b }
+    }
+}
+
+impl Constraint for BitConstraint {
+    fn satisfied(&self) -> bool {
+        match (self.x.borrow().try_value(), self.b.borrow().try_value()) {
+            (Some(x), Some(b)) => (x >> self.bit) & 1 == b,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(BitPropagator::new(
+            self.x.clone(),
+            self.bit,
+            self.b.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.b.clone()]
+    }
+}
+
+pub struct BitPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    bit: u32,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl BitPropagator {
+    pub fn new(x: Rc<RefCell<Variable>>, bit: u32, b: Rc<RefCell<Variable>>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            bit,
+            b,
+        }
+    }
+}
+
+impl Propagator for BitPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.x
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+        self.b
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        if !self.b.borrow_mut().tighten(0, 1) {
+            return;
+        }
+        let mut has = [false; 2];
+        for val in self.x.borrow().iter() {
+            has[((val >> self.bit) & 1) as usize] = true;
+            if has[0] && has[1] {
+                break;
+            }
+        }
+        for (val, present) in has.iter().enumerate() {
+            if !present && !self.b.borrow_mut().remove(val as i64) {
+                return;
+            }
+        }
+        let b = self.b.borrow().try_value();
+        if let Some(b) = b {
+            let wrong: Vec<i64> = self
+                .x
+                .borrow()
+                .iter()
+                .filter(|val| (val >> self.bit) & 1 != b)
+                .collect();
+            for val in wrong {
+                if !self.x.borrow_mut().remove(val) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.borrow().size() as usize
+    }
+}
+
+// vars[0] + vars[1] + ... + vars[vars.len() - 1] is odd if odd is true, even otherwise
+pub struct XorConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    odd: bool,
+}
+
+impl XorConstraint {
+    /// panics if vars is empty and odd is true, as an empty sum is never odd
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, odd: bool) -> Self {
+        assert!(!vars.is_empty() || !odd);
+        Self { vars, odd }
+    }
+    pub fn from_bools(vars: Vec<BoolVar>, odd: bool) -> Self {
+        Self::new(vars.into_iter().map(|v| v.into()).collect(), odd)
+    }
+}
+
+impl Constraint for XorConstraint {
+    fn satisfied(&self) -> bool {
+        let mut parity = 0;
+        for v in &self.vars {
+            match v.borrow().try_value() {
+                Some(x @ (0 | 1)) => parity ^= x,
+                _ => return false,
+            }
+        }
+        (parity == 1) == self.odd
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(XorPropagator::new(
+            self.vars.clone(),
+            self.odd,
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// waits until all variables but one are assigned, then the last one gets the value fixing the parity
+pub struct XorPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    odd: bool,
+}
+
+impl XorPropagator {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, odd: bool, id: usize) -> Self {
+        assert!(!vars.is_empty() || !odd);
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            odd,
+        }
+    }
+}
+
+impl Propagator for XorPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        for v in &self.vars {
+            if !v.borrow_mut().tighten(0, 1) {
+                return;
+            }
+        }
+        let mut parity = self.odd as i64;
+        let mut free = None;
+        for (i, v) in self.vars.iter().enumerate() {
+            match v.borrow().try_value() {
+                Some(x) => parity ^= x,
+                None if free.is_none() => free = Some(i),
+                None => return,
+            }
+        }
+        // parity is now the value the free variable needs
+        match free {
+            Some(i) => {
+                self.vars[i].borrow_mut().assign(parity);
+            }
+            None if parity != 0 => self.vars[0].borrow().fail(),
+            None => {}
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+    }
+}
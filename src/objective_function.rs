@@ -1,5 +1,175 @@
+use crate::constraint::Constraint;
+use crate::linear::LinearInequalityPropagator;
+use crate::propagator::Propagator;
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
 // function to minimize
 pub trait ObjectiveFunction {
     fn eval(&self) -> i64;
     fn bound(&self) -> i64;
+    /// components of the objective, minimized in lexicographic order
+    fn eval_lex(&self) -> Vec<i64> {
+        vec![self.eval()]
+    }
+    /// lower bounds of the components of the objective
+    fn bound_lex(&self) -> Vec<i64> {
+        vec![self.bound()]
+    }
+    /// constraint enforcing eval() <= bound, so that the objective takes part in propagation,
+    /// None leaves only bound() for pruning
+    /// the search posts it at the first improving solution and lowers bound after each next one,
+    /// so its propagators have to read bound whenever they run, i64::MAX stands for no bound
+    /// ignored for objectives with several components
+    fn as_constraint(&self, _bound: Rc<Cell<i64>>) -> Option<Box<dyn Constraint>> {
+        None
+    }
+}
+
+/// minimizes objectives[0], then objectives[1] among solutions with the best objectives[0], and so on
+/// eval() and bound() refer to the primary objective, the full tuple is given by eval_lex() and bound_lex()
+/// a solution replaces the best one only if its tuple is strictly smaller,
+/// so among solutions with equal tuples the first one found is kept
+pub struct LexObjective {
+    objectives: Vec<Box<dyn ObjectiveFunction>>,
+}
+
+impl LexObjective {
+    pub fn new(objectives: Vec<Box<dyn ObjectiveFunction>>) -> Self {
+        assert!(!objectives.is_empty());
+        Self { objectives }
+    }
+}
+
+impl ObjectiveFunction for LexObjective {
+    fn eval(&self) -> i64 {
+        self.objectives[0].eval()
+    }
+
+    fn bound(&self) -> i64 {
+        self.objectives[0].bound()
+    }
+
+    fn eval_lex(&self) -> Vec<i64> {
+        self.objectives.iter().flat_map(|o| o.eval_lex()).collect()
+    }
+
+    fn bound_lex(&self) -> Vec<i64> {
+        self.objectives.iter().flat_map(|o| o.bound_lex()).collect()
+    }
+}
+
+/// sum coef[i] * vars[i]
+pub struct LinearObjective {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    coef: Vec<i64>,
+}
+
+impl LinearObjective {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, coef: Vec<i64>) -> Self {
+        assert!(vars.len() == coef.len());
+        Self { vars, coef }
+    }
+}
+
+impl ObjectiveFunction for LinearObjective {
+    fn eval(&self) -> i64 {
+        let mut sum = 0;
+        for (var, c) in self.vars.iter().zip(self.coef.iter()) {
+            sum += var.borrow().value() * c;
+        }
+        sum
+    }
+
+    fn bound(&self) -> i64 {
+        let mut sum = 0;
+        for (var, c) in self.vars.iter().zip(self.coef.iter()) {
+            if *c > 0 {
+                sum += var.borrow().get_lb() * c;
+            } else {
+                sum += var.borrow().get_ub() * c;
+            }
+        }
+        sum
+    }
+
+    fn as_constraint(&self, bound: Rc<Cell<i64>>) -> Option<Box<dyn Constraint>> {
+        Some(Box::new(LinearCut {
+            vars: self.vars.clone(),
+            coef: self.coef.clone(),
+            bound,
+        }))
+    }
+}
+
+// sum coef[i] * vars[i] <= bound, with bound changing over time
+struct LinearCut {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    coef: Vec<i64>,
+    bound: Rc<Cell<i64>>,
+}
+
+impl Constraint for LinearCut {
+    fn satisfied(&self) -> bool {
+        let mut sum = 0;
+        for (var, c) in self.vars.iter().zip(self.coef.iter()) {
+            let Some(x) = var.borrow().try_value() else {
+                return false;
+            };
+            sum += x * c;
+        }
+        sum <= self.bound.get()
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(LinearInequalityPropagator::with_shared_bound(
+            self.vars.clone(),
+            self.coef.clone(),
+            self.bound.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// extra lower bound on the objective, usually derived from a constraint
+/// the solver prunes with the maximum of the objective bound and all extra bounds,
+/// so every extra bound has to be valid for the objective it is added with
+pub trait ObjectiveBound {
+    fn bound(&self) -> i64;
+}
+
+/// lower bound on sum of vars, when vars are pairwise different
+/// variables are sorted by lower bound, and each one takes the smallest value
+/// not below its lower bound and above the value of the previous one
+pub struct AllDifferentSumBound {
+    vars: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl AllDifferentSumBound {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>) -> Self {
+        Self { vars }
+    }
+}
+
+impl ObjectiveBound for AllDifferentSumBound {
+    fn bound(&self) -> i64 {
+        let mut lbs: Vec<i64> = self.vars.iter().map(|v| v.borrow().get_lb()).collect();
+        lbs.sort();
+        let mut sum = 0;
+        let mut prev = i64::MIN;
+        for lb in lbs {
+            let x = if prev == i64::MIN { lb } else { i64::max(lb, prev + 1) };
+            sum += x;
+            prev = x;
+        }
+        sum
+    }
 }
@@ -1,10 +1,41 @@
+use crate::variable::Variable;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// domain fact used in explanations of prunings
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Reason {
+    /// the value was removed by a search decision
+    Decision,
+    /// variable >= value
+    LowerBound,
+    /// variable <= value
+    UpperBound,
+    /// variable != value
+    Removed,
+}
+
+pub type Explanation = Vec<(Rc<RefCell<Variable>>, i64, Reason)>;
+
+/// largest domain size among vars, convenient for estimated_cost of propagators running a matching
+pub fn max_domain_size(vars: &[Rc<RefCell<Variable>>]) -> usize {
+    vars.iter().map(|v| v.borrow().size() as usize).max().unwrap_or(0)
+}
+
+/// priorities of propagators, queued propagators of higher priority run first
+pub const PRIORITY_LOW: u8 = 0;
+pub const PRIORITY_MEDIUM: u8 = 1;
+pub const PRIORITY_HIGH: u8 = 2;
+
 pub struct PropagatorControlBlock {
     pub has_new_events: bool,
     pub queued: bool,
     pub id: usize,
+    /// returned by the default Propagator::priority, PRIORITY_MEDIUM unless changed
+    pub priority: u8,
+    /// the propagator only runs while every variable here is assigned to its value,
+    /// filled by Solver::create_guarded_propagators
+    pub guards: Vec<(Rc<RefCell<Variable>>, i64)>,
 }
 
 impl PropagatorControlBlock {
@@ -13,6 +44,8 @@ impl PropagatorControlBlock {
             has_new_events: false,
             queued: false,
             id,
+            priority: PRIORITY_MEDIUM,
+            guards: Vec::new(),
         }
     }
 }
@@ -26,10 +59,30 @@ pub trait Propagator {
 
     fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock;
 
-    fn is_idemponent(&self) -> bool {
+    fn is_idempotent(&self) -> bool {
         false
     }
 
+    /// rough amount of work done by one propagate() call, e.g. arity for a linear constraint
+    /// informational only, the engine never reads it and queue order is decided by priority()
+    /// so it does not have to be precise
+    fn estimated_cost(&self) -> usize {
+        1
+    }
+
+    /// one of PRIORITY_LOW, PRIORITY_MEDIUM and PRIORITY_HIGH, larger values are treated as high
+    /// cheap bounds propagators should be high, flow based ones low
+    /// the order only affects speed, the fixpoint is the same
+    fn priority(&self) -> u8 {
+        self.get_cb().priority
+    }
+
+    /// returns domain facts which imply that val can be removed from var's domain
+    /// explanation is computed from the current domains, so it is only valid until backtracking
+    fn explain(&self, var: Rc<RefCell<Variable>>, val: i64) -> Explanation {
+        vec![(var, val, Reason::Decision)]
+    }
+
     fn new_event(&mut self) {
         self.get_cb_mut().has_new_events = true;
     }
@@ -0,0 +1,194 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::{Solver, SolverState};
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// state after reading symbol x in state q, values outside of the alphabet have no transition
+fn next_state(transitions: &[Vec<usize>], q: usize, x: i64) -> Option<usize> {
+    if x < 0 {
+        return None;
+    }
+    transitions[q].get(x as usize).cloned()
+}
+
+/// vars[0], ..., vars[n - 1] spell a word accepted by the DFA
+/// transitions[q][x] is the state after reading symbol x in state q
+pub struct RegularConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    transitions: Vec<Vec<usize>>,
+    start: usize,
+    accepting: Vec<usize>,
+}
+
+impl RegularConstraint {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        transitions: Vec<Vec<usize>>,
+        start: usize,
+        accepting: Vec<usize>,
+    ) -> Self {
+        assert!(start < transitions.len());
+        for row in &transitions {
+            assert!(row.iter().all(|q| *q < transitions.len()));
+        }
+        Self {
+            vars,
+            transitions,
+            start,
+            accepting,
+        }
+    }
+}
+
+impl Constraint for RegularConstraint {
+    fn satisfied(&self) -> bool {
+        let mut q = self.start;
+        for v in &self.vars {
+            let Some(x) = v.borrow().try_value() else {
+                return false;
+            };
+            match next_state(&self.transitions, q, x) {
+                Some(next) => q = next,
+                None => {
+                    return false;
+                }
+            }
+        }
+        self.accepting.contains(&q)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(RegularPropagator::new(
+            self.vars.clone(),
+            self.transitions.clone(),
+            self.start,
+            self.accepting.clone(),
+            solver.solver_state(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+}
+
+/// domain consistent propagator on the layered graph of the DFA,
+/// layer i holds the states reachable after reading i symbols
+pub struct RegularPropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    transitions: Vec<Vec<usize>>,
+    start: usize,
+    accepting: Vec<usize>,
+    // failures go here, as there may be no variables to fail through
+    solver_state: Rc<RefCell<SolverState>>,
+}
+
+impl RegularPropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        transitions: Vec<Vec<usize>>,
+        start: usize,
+        accepting: Vec<usize>,
+        solver_state: Rc<RefCell<SolverState>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            transitions,
+            start,
+            accepting,
+            solver_state,
+        }
+    }
+}
+
+impl Propagator for RegularPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.vars.len();
+        let states = self.transitions.len();
+        let domains: Vec<Vec<i64>> = self
+            .vars
+            .iter()
+            .map(|v| v.borrow().iter().collect())
+            .collect();
+        // forward pass, states reachable from the start
+        let mut layers = vec![vec![false; states]; n + 1];
+        layers[0][self.start] = true;
+        for i in 0..n {
+            for q in 0..states {
+                if !layers[i][q] {
+                    continue;
+                }
+                for x in domains[i].iter().cloned() {
+                    if let Some(next) = next_state(&self.transitions, q, x) {
+                        layers[i + 1][next] = true;
+                    }
+                }
+            }
+        }
+        // backward pass, keep only states from which an accepting state is reachable
+        for (q, reachable) in layers[n].iter_mut().enumerate() {
+            if !self.accepting.contains(&q) {
+                *reachable = false;
+            }
+        }
+        for i in (0..n).rev() {
+            for q in 0..states {
+                if layers[i][q] {
+                    layers[i][q] = domains[i].iter().any(|x| {
+                        next_state(&self.transitions, q, *x).is_some_and(|next| layers[i + 1][next])
+                    });
+                }
+            }
+        }
+        if !layers[0][self.start] {
+            self.solver_state.borrow_mut().fail();
+            return;
+        }
+        for i in 0..n {
+            for x in domains[i].iter().cloned() {
+                let supported = (0..states).any(|q| {
+                    layers[i][q]
+                        && next_state(&self.transitions, q, x)
+                            .is_some_and(|next| layers[i + 1][next])
+                });
+                if !supported {
+                    self.vars[i].borrow_mut().remove(x);
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len()
+            * self.transitions.len()
+            * self.transitions.first().map_or(1, |row| row.len())
+    }
+}
@@ -0,0 +1,489 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::Solver;
+use crate::variable::{domains_disjoint, Variable};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// b = 1 <=> x = y
+pub struct ReifiedEqualityConstraint {
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl ReifiedEqualityConstraint {
+    pub fn new(x: Rc<RefCell<Variable>>, y: Rc<RefCell<Variable>>, b: Rc<RefCell<Variable>>) -> Self {
+        Self { x, y, b }
+    }
+}
+
+impl Constraint for ReifiedEqualityConstraint {
+    fn satisfied(&self) -> bool {
+        match (
+            self.x.borrow().try_value(),
+            self.y.borrow().try_value(),
+            self.b.borrow().try_value(),
+        ) {
+            (Some(x), Some(y), Some(b)) => b == (x == y) as i64,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(ReifiedEqualityPropagator::new(
+            self.x.clone(),
+            self.y.clone(),
+            self.b.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone(), self.b.clone()]
+    }
+}
+
+pub struct ReifiedEqualityPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl ReifiedEqualityPropagator {
+    pub fn new(
+        x: Rc<RefCell<Variable>>,
+        y: Rc<RefCell<Variable>>,
+        b: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+            b,
+        }
+    }
+
+    // removes values of from missing in to
+    fn intersect(from: &Rc<RefCell<Variable>>, to: &Rc<RefCell<Variable>>) -> bool {
+        let missing: Vec<i64> = from
+            .borrow()
+            .iter()
+            .filter(|val| !to.borrow().possible(*val))
+            .collect();
+        for val in missing {
+            if !from.borrow_mut().remove(val) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn propagate_fixed(&mut self) {
+        if self.b.borrow().value() == 1 {
+            if Self::intersect(&self.x, &self.y) {
+                Self::intersect(&self.y, &self.x);
+            }
+        } else {
+            let x = self.x.borrow().try_value();
+            if let Some(x) = x {
+                if !self.y.borrow_mut().remove(x) {
+                    return;
+                }
+            }
+            let y = self.y.borrow().try_value();
+            if let Some(y) = y {
+                self.x.borrow_mut().remove(y);
+            }
+        }
+    }
+}
+
+impl Propagator for ReifiedEqualityPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.x
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+        self.y
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+        self.b
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        if !self.b.borrow_mut().tighten(0, 1) {
+            return;
+        }
+        if self.b.borrow().is_assigned() {
+            return self.propagate_fixed();
+        }
+        if domains_disjoint(&self.x.borrow(), &self.y.borrow()) {
+            self.b.borrow_mut().assign(0);
+            return;
+        }
+        let x = self.x.borrow().try_value();
+        if x.is_some() && x == self.y.borrow().try_value() {
+            self.b.borrow_mut().assign(1);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn estimated_cost(&self) -> usize {
+        (self.x.borrow().size() + self.y.borrow().size()) as usize
+    }
+}
+
+/// b = 1 <=> x <= y + c
+pub struct ReifiedLessEqualConstraint {
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    c: i64,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl ReifiedLessEqualConstraint {
+    pub fn new(
+        x: Rc<RefCell<Variable>>,
+        y: Rc<RefCell<Variable>>,
+        c: i64,
+        b: Rc<RefCell<Variable>>,
+    ) -> Self {
+        Self { x, y, c, b }
+    }
+}
+
+impl Constraint for ReifiedLessEqualConstraint {
+    fn satisfied(&self) -> bool {
+        match (
+            self.x.borrow().try_value(),
+            self.y.borrow().try_value(),
+            self.b.borrow().try_value(),
+        ) {
+            (Some(x), Some(y), Some(b)) => b == (x <= y + self.c) as i64,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(ReifiedLessEqualPropagator::new(
+            self.x.clone(),
+            self.y.clone(),
+            self.c,
+            self.b.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone(), self.y.clone(), self.b.clone()]
+    }
+}
+
+pub struct ReifiedLessEqualPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+    c: i64,
+    b: Rc<RefCell<Variable>>,
+}
+
+impl ReifiedLessEqualPropagator {
+    pub fn new(
+        x: Rc<RefCell<Variable>>,
+        y: Rc<RefCell<Variable>>,
+        c: i64,
+        b: Rc<RefCell<Variable>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+            c,
+            b,
+        }
+    }
+}
+
+impl Propagator for ReifiedLessEqualPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in [&self.x, &self.y] {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+        self.b
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        if !self.b.borrow_mut().tighten(0, 1) {
+            return;
+        }
+        let b = self.b.borrow().try_value();
+        match b {
+            Some(1) => {
+                // x <= y + c
+                let ub = self.y.borrow().get_ub() + self.c;
+                if self.x.borrow_mut().set_ub(ub) {
+                    let lb = self.x.borrow().get_lb() - self.c;
+                    self.y.borrow_mut().set_lb(lb);
+                }
+            }
+            Some(_) => {
+                // y <= x - c - 1
+                let ub = self.x.borrow().get_ub() - self.c - 1;
+                if self.y.borrow_mut().set_ub(ub) {
+                    let lb = self.y.borrow().get_lb() + self.c + 1;
+                    self.x.borrow_mut().set_lb(lb);
+                }
+            }
+            None => {
+                if self.x.borrow().get_ub() <= self.y.borrow().get_lb() + self.c {
+                    self.b.borrow_mut().assign(1);
+                } else if self.x.borrow().get_lb() > self.y.borrow().get_ub() + self.c {
+                    self.b.borrow_mut().assign(0);
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+fn new_reified_bool(
+    solver: &mut Solver,
+    x: &Rc<RefCell<Variable>>,
+    y: &Rc<RefCell<Variable>>,
+    op: &str,
+) -> Rc<RefCell<Variable>> {
+    let name = format!("{} {} {}", x.borrow().name, op, y.borrow().name);
+    solver.new_variable(0, 1, name)
+}
+
+/// returns a new boolean b, b = 1 <=> x <= y
+pub fn reify_le(
+    solver: &mut Solver,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+) -> Rc<RefCell<Variable>> {
+    let b = new_reified_bool(solver, &x, &y, "<=");
+    solver.add_constraint(Box::new(ReifiedLessEqualConstraint::new(x, y, 0, b.clone())));
+    b
+}
+
+/// returns a new boolean b, b = 1 <=> x < y
+pub fn reify_lt(
+    solver: &mut Solver,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+) -> Rc<RefCell<Variable>> {
+    let b = new_reified_bool(solver, &x, &y, "<");
+    solver.add_constraint(Box::new(ReifiedLessEqualConstraint::new(x, y, -1, b.clone())));
+    b
+}
+
+/// returns a new boolean b, b = 1 <=> x = y
+pub fn reify_eq(
+    solver: &mut Solver,
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+) -> Rc<RefCell<Variable>> {
+    let b = new_reified_bool(solver, &x, &y, "==");
+    solver.add_constraint(Box::new(ReifiedEqualityConstraint::new(x, y, b.clone())));
+    b
+}
+
+/// b = 1 <=> c holds, notc has to hold exactly when c is violated
+pub struct ReifiedConstraint {
+    inner: IfThenElseConstraint,
+}
+
+impl ReifiedConstraint {
+    pub fn new(
+        b: Rc<RefCell<Variable>>,
+        c: Rc<RefCell<dyn Constraint>>,
+        notc: Rc<RefCell<dyn Constraint>>,
+    ) -> Self {
+        Self {
+            inner: IfThenElseConstraint::new(b, c, notc),
+        }
+    }
+
+    /// takes notc from c.negation(), None if c has no negation
+    pub fn auto(b: Rc<RefCell<Variable>>, c: Rc<RefCell<dyn Constraint>>) -> Option<Self> {
+        let notc = c.borrow().negation()?;
+        Some(Self::new(b, c, notc))
+    }
+}
+
+impl Constraint for ReifiedConstraint {
+    fn satisfied(&self) -> bool {
+        self.inner.satisfied()
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        self.inner.create_propagators(solver);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.inner.variables()
+    }
+}
+
+/// then_c holds if cond = 1, else_c holds if cond = 0
+/// propagators of a branch only run once cond selects it
+pub struct IfThenElseConstraint {
+    cond: Rc<RefCell<Variable>>,
+    then_c: Rc<RefCell<dyn Constraint>>,
+    else_c: Rc<RefCell<dyn Constraint>>,
+}
+
+impl IfThenElseConstraint {
+    pub fn new(
+        cond: Rc<RefCell<Variable>>,
+        then_c: Rc<RefCell<dyn Constraint>>,
+        else_c: Rc<RefCell<dyn Constraint>>,
+    ) -> Self {
+        Self {
+            cond,
+            then_c,
+            else_c,
+        }
+    }
+}
+
+impl Constraint for IfThenElseConstraint {
+    fn satisfied(&self) -> bool {
+        match self.cond.borrow().try_value() {
+            Some(1) => self.then_c.borrow().satisfied(),
+            Some(0) => self.else_c.borrow().satisfied(),
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        solver.create_guarded_propagators(&*self.then_c.borrow(), self.cond.clone(), 1);
+        solver.create_guarded_propagators(&*self.else_c.borrow(), self.cond.clone(), 0);
+        let p = Rc::new(RefCell::new(IfThenElsePropagator::new(
+            self.cond.clone(),
+            self.then_c.clone(),
+            self.else_c.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        let mut vars = vec![self.cond.clone()];
+        vars.extend(self.then_c.borrow().variables());
+        vars.extend(self.else_c.borrow().variables());
+        vars
+    }
+}
+
+/// rules out the value of cond selecting a branch which is violated by assigned variables
+pub struct IfThenElsePropagator {
+    pcb: PropagatorControlBlock,
+    cond: Rc<RefCell<Variable>>,
+    then_c: Rc<RefCell<dyn Constraint>>,
+    else_c: Rc<RefCell<dyn Constraint>>,
+    then_vars: Vec<Rc<RefCell<Variable>>>,
+    else_vars: Vec<Rc<RefCell<Variable>>>,
+}
+
+impl IfThenElsePropagator {
+    pub fn new(
+        cond: Rc<RefCell<Variable>>,
+        then_c: Rc<RefCell<dyn Constraint>>,
+        else_c: Rc<RefCell<dyn Constraint>>,
+        id: usize,
+    ) -> Self {
+        let then_vars = then_c.borrow().variables();
+        let else_vars = else_c.borrow().variables();
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            cond,
+            then_c,
+            else_c,
+            then_vars,
+            else_vars,
+        }
+    }
+
+    fn violated(c: &Rc<RefCell<dyn Constraint>>, vars: &[Rc<RefCell<Variable>>]) -> bool {
+        vars.iter().all(|v| v.borrow().is_assigned()) && !c.borrow().satisfied()
+    }
+}
+
+impl Propagator for IfThenElsePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.cond
+            .borrow_mut()
+            .add_listener(self_pointer.clone(), Event::Modified);
+        for v in self.then_vars.iter().chain(self.else_vars.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Assigned);
+        }
+    }
+
+    fn propagate(&mut self) {
+        if !self.cond.borrow_mut().tighten(0, 1) {
+            return;
+        }
+        if self.cond.borrow().is_assigned() {
+            return;
+        }
+        if Self::violated(&self.then_c, &self.then_vars) && !self.cond.borrow_mut().remove(1) {
+            return;
+        }
+        if Self::violated(&self.else_c, &self.else_vars) {
+            self.cond.borrow_mut().remove(0);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.then_vars.len() + self.else_vars.len()
+    }
+}
@@ -40,6 +40,9 @@ pub fn compute_scc(gr: &Vec<Vec<usize>>) -> Vec<Vec<usize>> {
     used.fill(false);
     let mut ans = Vec::new();
     for v in order.drain(..) {
+        if used[v] {
+            continue;
+        }
         let mut component = Vec::new();
         mark_component(v, &grt, &mut used, &mut component);
         ans.push(component);
@@ -0,0 +1,720 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::linear::LinearInequalityConstraint;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// tasks [start_a, start_a + duration_a) and [start_b, start_b + duration_b) do not overlap
+/// tasks with zero duration never overlap anything
+pub struct NoOverlapPair {
+    start_a: Rc<RefCell<Variable>>,
+    duration_a: i64,
+    start_b: Rc<RefCell<Variable>>,
+    duration_b: i64,
+}
+
+impl NoOverlapPair {
+    pub fn new(
+        start_a: Rc<RefCell<Variable>>,
+        duration_a: i64,
+        start_b: Rc<RefCell<Variable>>,
+        duration_b: i64,
+    ) -> Self {
+        Self {
+            start_a,
+            duration_a,
+            start_b,
+            duration_b,
+        }
+    }
+}
+
+impl Constraint for NoOverlapPair {
+    fn satisfied(&self) -> bool {
+        let a = self.start_a.borrow().try_value();
+        let b = self.start_b.borrow().try_value();
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                self.duration_a == 0
+                    || self.duration_b == 0
+                    || a + self.duration_a <= b
+                    || b + self.duration_b <= a
+            }
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(NoOverlapPairPropagator::new(
+            self.start_a.clone(),
+            self.duration_a,
+            self.start_b.clone(),
+            self.duration_b,
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.start_a.clone(), self.start_b.clone()]
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        // zero length tasks never overlap, OverlapPair can not express that
+        if self.duration_a == 0 || self.duration_b == 0 {
+            return None;
+        }
+        Some(Rc::new(RefCell::new(OverlapPair::new(
+            self.start_a.clone(),
+            self.duration_a,
+            self.start_b.clone(),
+            self.duration_b,
+        ))))
+    }
+}
+
+/// tasks [start_a, start_a + duration_a) and [start_b, start_b + duration_b) overlap,
+/// durations are positive
+/// (b = 1 <=> overlap) is ReifiedConstraint::auto(b, OverlapPair)
+pub struct OverlapPair {
+    start_a: Rc<RefCell<Variable>>,
+    duration_a: i64,
+    start_b: Rc<RefCell<Variable>>,
+    duration_b: i64,
+}
+
+impl OverlapPair {
+    pub fn new(
+        start_a: Rc<RefCell<Variable>>,
+        duration_a: i64,
+        start_b: Rc<RefCell<Variable>>,
+        duration_b: i64,
+    ) -> Self {
+        assert!(duration_a > 0 && duration_b > 0);
+        Self {
+            start_a,
+            duration_a,
+            start_b,
+            duration_b,
+        }
+    }
+}
+
+impl Constraint for OverlapPair {
+    fn satisfied(&self) -> bool {
+        let a = self.start_a.borrow().try_value();
+        let b = self.start_b.borrow().try_value();
+        match (a, b) {
+            (Some(a), Some(b)) => a < b + self.duration_b && b < a + self.duration_a,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        // start_a - start_b <= duration_b - 1 and start_b - start_a <= duration_a - 1
+        let ab = vec![self.start_a.clone(), self.start_b.clone()];
+        LinearInequalityConstraint::new(ab.clone(), vec![1, -1], self.duration_b - 1)
+            .create_propagators(solver);
+        LinearInequalityConstraint::new(ab, vec![-1, 1], self.duration_a - 1)
+            .create_propagators(solver);
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.start_a.clone(), self.start_b.clone()]
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        Some(Rc::new(RefCell::new(NoOverlapPair::new(
+            self.start_a.clone(),
+            self.duration_a,
+            self.start_b.clone(),
+            self.duration_b,
+        ))))
+    }
+}
+
+// [a, a + da) and [b, b + db) with positive lengths do not overlap, returns false on failure
+fn push_apart(a: &mut Variable, da: i64, b: &mut Variable, db: i64) -> bool {
+    let a_first = a.get_lb() + da <= b.get_ub();
+    let b_first = b.get_lb() + db <= a.get_ub();
+    if !a_first && !b_first {
+        a.fail();
+        return false;
+    }
+    if !a_first {
+        let a_lb = b.get_lb() + db;
+        let b_ub = a.get_ub() - db;
+        if !a.set_lb(a_lb) || !b.set_ub(b_ub) {
+            return false;
+        }
+    }
+    if !b_first {
+        let b_lb = a.get_lb() + da;
+        let a_ub = b.get_ub() - da;
+        if !b.set_lb(b_lb) || !a.set_ub(a_ub) {
+            return false;
+        }
+    }
+    true
+}
+
+pub struct NoOverlapPairPropagator {
+    pcb: PropagatorControlBlock,
+    start_a: Rc<RefCell<Variable>>,
+    duration_a: i64,
+    start_b: Rc<RefCell<Variable>>,
+    duration_b: i64,
+}
+
+impl NoOverlapPairPropagator {
+    pub fn new(
+        start_a: Rc<RefCell<Variable>>,
+        duration_a: i64,
+        start_b: Rc<RefCell<Variable>>,
+        duration_b: i64,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            start_a,
+            duration_a,
+            start_b,
+            duration_b,
+        }
+    }
+}
+
+impl Propagator for NoOverlapPairPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in [&self.start_a, &self.start_b] {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        if self.duration_a == 0 || self.duration_b == 0 {
+            return;
+        }
+        // a task of positive length always overlaps itself
+        if Rc::ptr_eq(&self.start_a, &self.start_b) {
+            self.start_a.borrow().fail();
+            return;
+        }
+        push_apart(
+            &mut self.start_a.borrow_mut(),
+            self.duration_a,
+            &mut self.start_b.borrow_mut(),
+            self.duration_b,
+        );
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// at every time point, the total demand of tasks running then is at most capacity
+/// task i runs in [starts[i], starts[i] + durations[i])
+pub struct CumulativeConstraint {
+    starts: Vec<Rc<RefCell<Variable>>>,
+    durations: Vec<i64>,
+    demands: Vec<i64>,
+    capacity: i64,
+}
+
+impl CumulativeConstraint {
+    pub fn new(
+        starts: Vec<Rc<RefCell<Variable>>>,
+        durations: Vec<i64>,
+        demands: Vec<i64>,
+        capacity: i64,
+    ) -> Self {
+        assert!(starts.len() == durations.len() && starts.len() == demands.len());
+        assert!(durations.iter().chain(demands.iter()).all(|x| *x >= 0));
+        Self {
+            starts,
+            durations,
+            demands,
+            capacity,
+        }
+    }
+}
+
+// maximal segments [s, e) with constant positive total demand h, sorted by time
+fn profile(parts: impl Iterator<Item = (i64, i64, i64)>) -> Vec<(i64, i64, i64)> {
+    let mut events = Vec::new();
+    for (s, e, h) in parts {
+        if s < e && h > 0 {
+            events.push((s, h));
+            events.push((e, -h));
+        }
+    }
+    events.sort();
+    let mut segments = Vec::new();
+    let mut height = 0;
+    for (i, (t, delta)) in events.iter().enumerate() {
+        height += delta;
+        if let Some((next, _)) = events.get(i + 1) {
+            if height > 0 && next > t {
+                segments.push((*t, *next, height));
+            }
+        }
+    }
+    segments
+}
+
+impl Constraint for CumulativeConstraint {
+    fn satisfied(&self) -> bool {
+        let mut parts = Vec::with_capacity(self.starts.len());
+        for (i, v) in self.starts.iter().enumerate() {
+            let Some(s) = v.borrow().try_value() else {
+                return false;
+            };
+            parts.push((s, s + self.durations[i], self.demands[i]));
+        }
+        profile(parts.into_iter()).iter().all(|(_, _, h)| *h <= self.capacity)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(CumulativeTimeTablePropagator::new(
+            self.starts.clone(),
+            self.durations.clone(),
+            self.demands.clone(),
+            self.capacity,
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.starts.clone()
+    }
+}
+
+/// time-table filtering, a task surely runs in [ub, lb + duration) if the interval is not empty (mandatory part)
+/// a task is never placed where the mandatory parts of the other tasks leave too little capacity for it
+pub struct CumulativeTimeTablePropagator {
+    pcb: PropagatorControlBlock,
+    starts: Vec<Rc<RefCell<Variable>>>,
+    durations: Vec<i64>,
+    demands: Vec<i64>,
+    capacity: i64,
+}
+
+impl CumulativeTimeTablePropagator {
+    pub fn new(
+        starts: Vec<Rc<RefCell<Variable>>>,
+        durations: Vec<i64>,
+        demands: Vec<i64>,
+        capacity: i64,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            starts,
+            durations,
+            demands,
+            capacity,
+        }
+    }
+
+    fn mandatory_part(&self, i: usize) -> (i64, i64, i64) {
+        let v = self.starts[i].borrow();
+        (v.get_ub(), v.get_lb() + self.durations[i], self.demands[i])
+    }
+}
+
+impl Propagator for CumulativeTimeTablePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.starts {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.starts.len();
+        let full = profile((0..n).map(|i| self.mandatory_part(i)));
+        if full.iter().any(|(_, _, h)| *h > self.capacity) {
+            self.starts[0].borrow().fail();
+            return;
+        }
+        for i in 0..n {
+            let (d, r) = (self.durations[i], self.demands[i]);
+            if d == 0 || r == 0 {
+                continue;
+            }
+            if r > self.capacity {
+                self.starts[i].borrow().fail();
+                return;
+            }
+            let others = profile((0..n).filter(|j| *j != i).map(|j| self.mandatory_part(j)));
+            let mut v = self.starts[i].borrow_mut();
+            // segments are disjoint and sorted, so one pass in each direction is enough
+            let mut lb = v.get_lb();
+            for (s, e, h) in others.iter().cloned() {
+                if h + r > self.capacity && s < lb + d && e > lb {
+                    lb = e;
+                }
+            }
+            let mut ub = v.get_ub();
+            for (s, e, h) in others.iter().rev().cloned() {
+                if h + r > self.capacity && s < ub + d && e > ub {
+                    ub = s - d;
+                }
+            }
+            if !v.set_lb(lb) || !v.set_ub(ub) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.starts.len() * self.starts.len()
+    }
+}
+
+/// no two tasks overlap, task i runs in [starts[i], starts[i] + durations[i])
+/// tasks with zero duration never overlap anything
+pub struct DisjunctiveConstraint {
+    starts: Vec<Rc<RefCell<Variable>>>,
+    durations: Vec<i64>,
+}
+
+impl DisjunctiveConstraint {
+    pub fn new(starts: Vec<Rc<RefCell<Variable>>>, durations: Vec<i64>) -> Self {
+        assert!(starts.len() == durations.len());
+        assert!(durations.iter().all(|x| *x >= 0));
+        Self { starts, durations }
+    }
+}
+
+impl Constraint for DisjunctiveConstraint {
+    fn satisfied(&self) -> bool {
+        let mut tasks = Vec::with_capacity(self.starts.len());
+        for (i, v) in self.starts.iter().enumerate() {
+            let Some(s) = v.borrow().try_value() else {
+                return false;
+            };
+            if self.durations[i] > 0 {
+                tasks.push((s, s + self.durations[i]));
+            }
+        }
+        tasks.sort();
+        tasks.windows(2).all(|w| w[0].1 <= w[1].0)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(DisjunctivePropagator::new(
+            self.starts.clone(),
+            self.durations.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.starts.clone()
+    }
+}
+
+/// overload checking and edge-finding over task intervals
+/// a task interval is the set of tasks with earliest start and latest end inside the ones of two given tasks,
+/// if a task does not fit before the end of some task interval together with it, the task runs after all of it
+/// (and symmetrically for running before), intervals of a single task give the pairwise reasoning
+pub struct DisjunctivePropagator {
+    pcb: PropagatorControlBlock,
+    starts: Vec<Rc<RefCell<Variable>>>,
+    durations: Vec<i64>,
+}
+
+impl DisjunctivePropagator {
+    pub fn new(starts: Vec<Rc<RefCell<Variable>>>, durations: Vec<i64>, id: usize) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            starts,
+            durations,
+        }
+    }
+}
+
+impl Propagator for DisjunctivePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.starts {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let tasks: Vec<usize> = (0..self.starts.len())
+            .filter(|i| self.durations[*i] > 0)
+            .collect();
+        let d = &self.durations;
+        let est: Vec<i64> = self.starts.iter().map(|v| v.borrow().get_lb()).collect();
+        let lct: Vec<i64> = self
+            .starts
+            .iter()
+            .zip(d.iter())
+            .map(|(v, d)| v.borrow().get_ub() + d)
+            .collect();
+        let mut new_est = est.clone();
+        let mut new_lct = lct.clone();
+        for a in tasks.iter().cloned() {
+            for b in tasks.iter().cloned() {
+                if lct[b] <= est[a] {
+                    continue;
+                }
+                let inside = |j: usize| est[j] >= est[a] && lct[j] <= lct[b];
+                let total: i64 = tasks.iter().filter(|j| inside(**j)).map(|j| d[*j]).sum();
+                if total == 0 {
+                    continue;
+                }
+                if est[a] + total > lct[b] {
+                    self.starts[a].borrow().fail();
+                    return;
+                }
+                for i in tasks.iter().cloned() {
+                    if inside(i) {
+                        continue;
+                    }
+                    if i64::min(est[i], est[a]) + total + d[i] > lct[b] {
+                        new_est[i] = i64::max(new_est[i], est[a] + total);
+                    }
+                    if i64::max(lct[i], lct[b]) - total - d[i] < est[a] {
+                        new_lct[i] = i64::min(new_lct[i], lct[b] - total);
+                    }
+                }
+            }
+        }
+        for i in tasks {
+            let mut v = self.starts[i].borrow_mut();
+            if !v.set_lb(new_est[i]) || !v.set_ub(new_lct[i] - d[i]) {
+                return;
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.starts.len() * self.starts.len() * self.starts.len()
+    }
+}
+
+/// rectangles [x[i], x[i] + widths[i]) x [y[i], y[i] + heights[i]) do not overlap
+/// rectangles with zero width or height never overlap anything
+pub struct DiffnConstraint {
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+    widths: Vec<i64>,
+    heights: Vec<i64>,
+}
+
+impl DiffnConstraint {
+    pub fn new(
+        x: Vec<Rc<RefCell<Variable>>>,
+        y: Vec<Rc<RefCell<Variable>>>,
+        widths: Vec<i64>,
+        heights: Vec<i64>,
+    ) -> Self {
+        assert!(x.len() == y.len() && x.len() == widths.len() && x.len() == heights.len());
+        assert!(widths.iter().chain(heights.iter()).all(|x| *x >= 0));
+        Self {
+            x,
+            y,
+            widths,
+            heights,
+        }
+    }
+}
+
+impl Constraint for DiffnConstraint {
+    fn satisfied(&self) -> bool {
+        let mut placed = Vec::with_capacity(self.x.len());
+        for (x, y) in self.x.iter().zip(self.y.iter()) {
+            let (Some(x), Some(y)) = (x.borrow().try_value(), y.borrow().try_value()) else {
+                return false;
+            };
+            placed.push((x, y));
+        }
+        let empty = |i: usize| self.widths[i] == 0 || self.heights[i] == 0;
+        for i in 0..placed.len() {
+            for j in i + 1..placed.len() {
+                if empty(i) || empty(j) {
+                    continue;
+                }
+                let (xi, yi) = placed[i];
+                let (xj, yj) = placed[j];
+                let separated = xi + self.widths[i] <= xj
+                    || xj + self.widths[j] <= xi
+                    || yi + self.heights[i] <= yj
+                    || yj + self.heights[j] <= yi;
+                if !separated {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(DiffnPropagator::new(
+            self.x.clone(),
+            self.y.clone(),
+            self.widths.clone(),
+            self.heights.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.x.iter().chain(self.y.iter()).cloned().collect()
+    }
+}
+
+/// pairwise reasoning: once two rectangles can not be separated along one axis,
+/// they are pushed apart along the other one
+pub struct DiffnPropagator {
+    pcb: PropagatorControlBlock,
+    x: Vec<Rc<RefCell<Variable>>>,
+    y: Vec<Rc<RefCell<Variable>>>,
+    widths: Vec<i64>,
+    heights: Vec<i64>,
+}
+
+impl DiffnPropagator {
+    pub fn new(
+        x: Vec<Rc<RefCell<Variable>>>,
+        y: Vec<Rc<RefCell<Variable>>>,
+        widths: Vec<i64>,
+        heights: Vec<i64>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            x,
+            y,
+            widths,
+            heights,
+        }
+    }
+
+    // whether [a, a + da) and [b, b + db) can still be disjoint,
+    // intervals of positive length sharing a start variable always intersect
+    fn separable(a: &Rc<RefCell<Variable>>, da: i64, b: &Rc<RefCell<Variable>>, db: i64) -> bool {
+        if Rc::ptr_eq(a, b) {
+            return false;
+        }
+        let (a, b) = (a.borrow(), b.borrow());
+        a.get_lb() + da <= b.get_ub() || b.get_lb() + db <= a.get_ub()
+    }
+}
+
+impl Propagator for DiffnPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in self.x.iter().chain(self.y.iter()) {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::LowerBound);
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::UpperBound);
+        }
+    }
+
+    fn propagate(&mut self) {
+        let n = self.x.len();
+        for i in 0..n {
+            if self.widths[i] == 0 || self.heights[i] == 0 {
+                continue;
+            }
+            for j in i + 1..n {
+                if self.widths[j] == 0 || self.heights[j] == 0 {
+                    continue;
+                }
+                let (wi, wj) = (self.widths[i], self.widths[j]);
+                let (hi, hj) = (self.heights[i], self.heights[j]);
+                let x_free = Self::separable(&self.x[i], wi, &self.x[j], wj);
+                let y_free = Self::separable(&self.y[i], hi, &self.y[j], hj);
+                let ok = match (x_free, y_free) {
+                    (false, false) => {
+                        self.x[i].borrow().fail();
+                        false
+                    }
+                    (false, true) => push_apart(
+                        &mut self.y[i].borrow_mut(),
+                        hi,
+                        &mut self.y[j].borrow_mut(),
+                        hj,
+                    ),
+                    (true, false) => push_apart(
+                        &mut self.x[i].borrow_mut(),
+                        wi,
+                        &mut self.x[j].borrow_mut(),
+                        wj,
+                    ),
+                    (true, true) => true,
+                };
+                if !ok {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.x.len() * self.x.len()
+    }
+}
@@ -1,17 +1,21 @@
+use crate::config::{Config, RestartStrategy};
 use crate::constraint::Constraint;
-use crate::objective_function::ObjectiveFunction;
-use crate::propagator::Propagator;
+use crate::events::Event;
+use crate::objective_function::{ObjectiveBound, ObjectiveFunction};
+use crate::propagator::{Propagator, PRIORITY_HIGH};
 use crate::value_selector::ValueSelector;
-use crate::variable::Variable;
+use crate::variable::{BoolVar, DomainKind, Variable};
 use crate::variable_selector::VariableSelector;
 use std::boxed::Box;
-use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
+use std::time::Instant;
 
 pub struct SolverState {
     status: i32,
-    propagation_queue: VecDeque<Rc<RefCell<dyn Propagator>>>,
+    // one queue per priority, index is the priority
+    propagation_queue: Vec<VecDeque<Rc<RefCell<dyn Propagator>>>>,
     resched_current: bool,
 }
 
@@ -19,31 +23,123 @@ impl SolverState {
     pub fn new() -> Self {
         Self {
             status: 0,
-            propagation_queue: VecDeque::new(),
+            propagation_queue: vec![VecDeque::new(); PRIORITY_HIGH as usize + 1],
             resched_current: false,
         }
     }
     pub fn fail(&mut self) {
         self.status = -1
     }
+    /// the listener must not be borrowed mutably
     pub fn enqueue(&mut self, listener: Rc<RefCell<dyn Propagator>>) {
-        self.propagation_queue.push_back(listener);
+        let priority = u8::min(listener.borrow().priority(), PRIORITY_HIGH);
+        self.propagation_queue[priority as usize].push_back(listener);
+    }
+    // first queued propagator of the highest priority
+    fn pop(&mut self) -> Option<Rc<RefCell<dyn Propagator>>> {
+        self.propagation_queue
+            .iter_mut()
+            .rev()
+            .find_map(|queue| queue.pop_front())
+    }
+    fn queue_is_empty(&self) -> bool {
+        self.propagation_queue.iter().all(|queue| queue.is_empty())
     }
     pub fn reschedule(&mut self) {
         self.resched_current = true;
     }
 }
 
+/// counters of the last solve()
+/// they are updated as the search runs, see Solver::stats_handle
+#[derive(Clone, Default)]
+pub struct SearchStats {
+    /// nodes where propagation failed or the objective bound pruned the subtree
+    pub fails: usize,
+    pub nodes: usize,
+    /// number of search nodes on the current path, 0 outside of solve()
+    pub depth: usize,
+    pub max_depth: usize,
+    /// the search was stopped by a node, fail or time limit before exploring the whole tree
+    pub limit_reached: bool,
+    /// the limit was Config::time_limit
+    pub timed_out: bool,
+    /// no part of the tree was skipped, so the result is proven,
+    /// false after a satisfaction search stopping at its first solution
+    pub whole_tree_explored: bool,
+    /// times the search was unwound to the root by the restart strategy
+    pub restarts: usize,
+}
+
+// what the search does with the solutions it finds besides keeping the best one
+enum Enumeration {
+    Off,
+    Count(usize),
+    Store(Vec<Vec<i64>>),
+}
+
+impl Enumeration {
+    fn record(&mut self, solution: impl FnOnce() -> Vec<i64>) {
+        match self {
+            Self::Off => {}
+            Self::Count(count) => *count += 1,
+            Self::Store(solutions) => solutions.push(solution()),
+        }
+    }
+}
+
+// i-th element of the Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, ..., starting from i = 1
+fn luby(i: usize) -> usize {
+    let mut k = 1;
+    while (1 << k) - 1 < i {
+        k += 1;
+    }
+    if (1 << k) - 1 == i {
+        1 << (k - 1)
+    } else {
+        luby(i - (1 << (k - 1)) + 1)
+    }
+}
+
 pub struct Solver {
     constraints: Vec<Box<dyn Constraint>>,
     propagators: Vec<Rc<RefCell<dyn Propagator>>>,
+    // index of the constraint which created the propagator, by propagator id
+    propagator_owner: Vec<Option<usize>>,
     variables: Vec<Rc<RefCell<Variable>>>,
-    variable_selector: Box<dyn VariableSelector>,
-    value_selector: Box<dyn ValueSelector>,
+    variables_by_name: HashMap<String, Rc<RefCell<Variable>>>,
+    constants: HashMap<i64, Rc<RefCell<Variable>>>,
+    // guards given to propagators created inside create_guarded_propagators
+    guards: Vec<(Rc<RefCell<Variable>>, i64)>,
+    config: Config,
     state: Rc<RefCell<SolverState>>,
     objective: Option<Box<dyn ObjectiveFunction>>,
-    current_min: i64,
+    objective_bounds: Vec<Box<dyn ObjectiveBound>>,
+    // objective <= objective_cut_bound constraint posted by the search at the first improving solution,
+    // kept out of check_solution, as the best solution itself violates it
+    objective_cut: Option<Box<dyn Constraint>>,
+    objective_cut_propagators: Vec<Rc<RefCell<dyn Propagator>>>,
+    // best - 1 during a search, i64::MAX until its first improving solution
+    objective_cut_bound: Rc<Cell<i64>>,
+    // components of the best objective value, empty before the first solution
+    current_min: Vec<i64>,
     best_solution: Vec<i64>,
+    // Off for solve(), otherwise the search goes on after each solution of a satisfaction problem
+    enumeration: Enumeration,
+    last_improvement: Option<Instant>,
+    start: Option<Instant>,
+    stopped: bool,
+    // shared with the handles returned by stats_handle
+    stats: Rc<RefCell<SearchStats>>,
+    // failures allowed before the next restart, None without restarts
+    restart_limit: Option<usize>,
+    fails_since_restart: usize,
+    restarts: usize,
+    restarting: bool,
+    // number of search nodes on the current path
+    depth: usize,
+    // smallest objective bound among nodes left unexplored by a stopped search
+    open_bound: i64,
     propagator_id_ctr: usize,
 }
 
@@ -52,33 +148,113 @@ impl Solver {
         variable_selector: Box<dyn VariableSelector>,
         value_selector: Box<dyn ValueSelector>,
     ) -> Self {
+        Self::with_config(Config::new(variable_selector, value_selector))
+    }
+    pub fn with_config(config: Config) -> Self {
         Self {
             constraints: Vec::new(),
             propagators: Vec::new(),
+            propagator_owner: Vec::new(),
             variables: Vec::new(),
-            variable_selector,
-            value_selector,
+            variables_by_name: HashMap::new(),
+            constants: HashMap::new(),
+            guards: Vec::new(),
+            config,
             state: Rc::new(RefCell::new(SolverState::new())),
             objective: None,
-            current_min: i64::MAX,
+            objective_bounds: Vec::new(),
+            objective_cut: None,
+            objective_cut_propagators: Vec::new(),
+            objective_cut_bound: Rc::new(Cell::new(i64::MAX)),
+            current_min: Vec::new(),
             best_solution: Vec::new(),
+            enumeration: Enumeration::Off,
+            last_improvement: None,
+            start: None,
+            stopped: false,
+            stats: Rc::new(RefCell::new(SearchStats::default())),
+            restart_limit: None,
+            fails_since_restart: 0,
+            restarts: 0,
+            restarting: false,
+            depth: 0,
+            open_bound: i64::MAX,
             propagator_id_ctr: 0,
         }
     }
     pub fn add_constraint(&mut self, c: Box<dyn Constraint>) -> &mut dyn Constraint {
+        let first_id = self.propagator_id_ctr;
         c.create_propagators(self);
+        self.propagator_owner.resize(self.propagator_id_ctr, None);
+        for owner in &mut self.propagator_owner[first_id..] {
+            *owner = Some(self.constraints.len());
+        }
         self.constraints.push(c);
         let r = self.constraints.last_mut().unwrap().as_mut();
         r
     }
+    /// posts every constraint in the batch, in order
+    pub fn add_constraints(&mut self, constraints: Vec<Box<dyn Constraint>>) {
+        for c in constraints {
+            self.add_constraint(c);
+        }
+    }
     pub fn add_objective(&mut self, objective: Box<dyn ObjectiveFunction>) {
         self.objective = Some(objective);
     }
+    /// the bound has to be a valid lower bound of the objective
+    pub fn add_objective_bound(&mut self, bound: Box<dyn ObjectiveBound>) {
+        self.objective_bounds.push(bound);
+    }
     pub fn add_propagator(&mut self, p: Rc<RefCell<dyn Propagator>>) {
+        if !self.guards.is_empty() {
+            p.borrow_mut().get_cb_mut().guards = self.guards.clone();
+            Self::listen_guards(&p);
+        }
         self.propagators.push(p);
     }
+    /// creates the propagators of c so that they only run once cond is assigned to val
+    /// c is not added to the solver, checking it in satisfied() is up to the caller
+    pub fn create_guarded_propagators(
+        &mut self,
+        c: &dyn Constraint,
+        cond: Rc<RefCell<Variable>>,
+        val: i64,
+    ) {
+        self.guards.push((cond, val));
+        c.create_propagators(self);
+        self.guards.pop();
+    }
+    // a guarded propagator has to wake up when its guards get assigned
+    fn listen_guards(p: &Rc<RefCell<dyn Propagator>>) {
+        for (v, _) in p.borrow().get_cb().guards.iter() {
+            v.borrow_mut().add_listener(p.clone(), Event::Modified);
+        }
+    }
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+    /// the best objective value found, i64::MAX if there is none
+    /// for a lexicographic objective this is the primary component
     pub fn get_objective(&self) -> i64 {
-        self.current_min
+        self.current_min.first().cloned().unwrap_or(i64::MAX)
+    }
+    /// all components of the best objective value, empty if there is none
+    pub fn get_objective_values(&self) -> &[i64] {
+        &self.current_min
+    }
+    /// a snapshot of the counters
+    pub fn get_stats(&self) -> SearchStats {
+        self.stats.borrow().clone()
+    }
+    /// the counters shared with the solver, they can be read while solve() runs
+    /// and refer to the latest call afterwards
+    pub fn stats_handle(&self) -> Rc<RefCell<SearchStats>> {
+        self.stats.clone()
+    }
+    /// the state shared by all variables, propagators which may have no variables fail through it
+    pub fn solver_state(&self) -> Rc<RefCell<SolverState>> {
+        self.state.clone()
     }
     pub fn new_propagator_id(&mut self) -> usize {
         let id = self.propagator_id_ctr;
@@ -90,11 +266,71 @@ impl Solver {
             self.state.clone(),
             lb,
             ub,
-            name,
+            name.clone(),
+        )));
+        self.variables.push(var.clone());
+        self.variables_by_name.insert(name, var.clone());
+        var
+    }
+    /// new variable with domain [lb, ub] stored as kind
+    pub fn new_variable_with_kind(
+        &mut self,
+        lb: i64,
+        ub: i64,
+        kind: DomainKind,
+        name: String,
+    ) -> Rc<RefCell<Variable>> {
+        let var = Rc::new(RefCell::new(Variable::with_kind(
+            self.state.clone(),
+            lb,
+            ub,
+            kind,
+            name.clone(),
+        )));
+        self.variables.push(var.clone());
+        self.variables_by_name.insert(name, var.clone());
+        var
+    }
+    /// new variable with the union of the closed intervals [l, r] in ranges as domain
+    pub fn new_variable_with_ranges(
+        &mut self,
+        ranges: &[(i64, i64)],
+        name: String,
+    ) -> Rc<RefCell<Variable>> {
+        let var = Rc::new(RefCell::new(Variable::with_ranges(
+            self.state.clone(),
+            ranges,
+            name.clone(),
         )));
         self.variables.push(var.clone());
+        self.variables_by_name.insert(name, var.clone());
         var
     }
+    /// new variable with domain {0, 1}
+    pub fn new_bool(&mut self, name: String) -> BoolVar {
+        BoolVar::new(self.new_variable(0, 1, name))
+    }
+    /// returns variable with domain {v}, the same variable is returned for equal values
+    pub fn const_variable(&mut self, v: i64) -> Rc<RefCell<Variable>> {
+        if let Some(var) = self.constants.get(&v) {
+            return var.clone();
+        }
+        let var = Rc::new(RefCell::new(Variable::new(
+            self.state.clone(),
+            v,
+            v,
+            v.to_string(),
+        )));
+        self.variables.push(var.clone());
+        self.constants.insert(v, var.clone());
+        var
+    }
+    pub fn has_variable(&self, name: &str) -> bool {
+        self.variables_by_name.contains_key(name)
+    }
+    pub fn get_variable_by_name(&self, name: &str) -> Option<Rc<RefCell<Variable>>> {
+        self.variables_by_name.get(name).cloned()
+    }
     pub fn check_solution(&self) -> bool {
         for c in &self.constraints {
             if !c.satisfied() {
@@ -104,41 +340,252 @@ impl Solver {
         true
     }
 
+    /// human-readable summary of the model, every variable with its domain,
+    /// then every constraint with the names of its variables
+    pub fn dump_model(&self) -> String {
+        let mut out = String::new();
+        for v in &self.variables {
+            let v = v.borrow();
+            // domain as a list of maximal intervals
+            let mut ranges = Vec::new();
+            let mut lo = v.get_lb();
+            for (l, r) in v.domain.holes() {
+                ranges.push((lo, l - 1));
+                lo = r + 1;
+            }
+            ranges.push((lo, v.get_ub()));
+            let ranges: Vec<String> = ranges
+                .into_iter()
+                .map(|(l, r)| {
+                    if l == r {
+                        l.to_string()
+                    } else {
+                        format!("{}..{}", l, r)
+                    }
+                })
+                .collect();
+            out += &format!("var {} in {{{}}}\n", v.name, ranges.join(", "));
+        }
+        for c in &self.constraints {
+            let names: Vec<String> = c
+                .variables()
+                .iter()
+                .map(|v| v.borrow().name.clone())
+                .collect();
+            out += &format!("constraint {}({})\n", c.name(), names.join(", "));
+        }
+        out
+    }
+
     pub fn propagate(&mut self) -> bool {
-        while !self.state.borrow().propagation_queue.is_empty() {
+        let mut propagations = 0;
+        while !self.state.borrow().queue_is_empty() {
+            if Some(propagations) == self.config.max_propagations_per_node {
+                // stopping early is safe, remaining propagators stay queued until the next node
+                break;
+            }
+            propagations += 1;
             self.state.borrow_mut().resched_current = false;
-            let p = self
-                .state
-                .borrow_mut()
-                .propagation_queue
-                .pop_front()
-                .unwrap();
+            let p = self.state.borrow_mut().pop().unwrap();
             p.borrow_mut().dequeue();
             p.borrow_mut().clear_events();
-            p.borrow_mut().propagate();
+            let active = p
+                .borrow()
+                .get_cb()
+                .guards
+                .iter()
+                .all(|(v, val)| v.borrow().try_value() == Some(*val));
+            if active {
+                p.borrow_mut().propagate();
+            }
             p.borrow().listen(p.clone());
+            Self::listen_guards(&p);
             if self.state.borrow().status == -1 {
-                for prop in self.state.borrow_mut().propagation_queue.drain(..) {
+                self.bump_weights(p.borrow().get_id());
+                let queued: Vec<_> = self
+                    .state
+                    .borrow_mut()
+                    .propagation_queue
+                    .iter_mut()
+                    .flat_map(|queue| queue.drain(..))
+                    .collect();
+                for prop in queued {
                     prop.borrow_mut().dequeue();
                     prop.borrow().listen(prop.clone());
+                    Self::listen_guards(&prop);
                 }
                 return false;
             }
-            if self.state.borrow().resched_current && !p.borrow().is_idemponent() {
-                self.state
-                    .borrow_mut()
-                    .propagation_queue
-                    .push_back(p.clone());
+            if self.state.borrow().resched_current && !p.borrow().is_idempotent() {
+                self.state.borrow_mut().enqueue(p.clone());
                 p.borrow_mut().enqueue();
             }
         }
         true
     }
 
+    // the propagator failed, every variable of its constraint gets heavier
+    fn bump_weights(&self, id: usize) {
+        if let Some(Some(owner)) = self.propagator_owner.get(id) {
+            for v in self.constraints[*owner].variables() {
+                v.borrow_mut().weight += 1;
+            }
+        }
+    }
+
+    // every propagator runs at the root, even if none of its events ever fire
+    fn enqueue_all(&mut self) {
+        for p in &self.propagators {
+            if !p.borrow().is_queued() {
+                p.borrow_mut().enqueue();
+                self.state.borrow_mut().enqueue(p.clone());
+            }
+        }
+    }
+
+    /// runs every propagator until fixpoint without searching, returns false on failure
+    /// domains are not restored afterwards
+    pub fn propagate_all(&mut self) -> bool {
+        self.enqueue_all();
+        let res = self.propagate();
+        self.state.borrow_mut().status = 0;
+        res
+    }
+
+    // lower bounds of the objective components at the current node, None without objective
+    // extra bounds apply to the primary component
+    fn node_bound(&self) -> Option<Vec<i64>> {
+        let objective = self.objective.as_ref()?;
+        let mut bound = objective.bound_lex();
+        bound[0] = self
+            .objective_bounds
+            .iter()
+            .fold(bound[0], |acc, b| i64::max(acc, b.bound()));
+        Some(bound)
+    }
+
+    // the subtree of the current node is left unexplored
+    fn leave_open(&mut self) {
+        if let Some(bound) = self.node_bound() {
+            self.open_bound = i64::min(self.open_bound, bound[0]);
+        }
+    }
+
+    // failures allowed before restart number self.restarts
+    fn next_restart_limit(&self) -> Option<usize> {
+        // a restarted enumeration would find the same solutions again
+        if !matches!(self.enumeration, Enumeration::Off) && self.objective.is_none() {
+            return None;
+        }
+        match self.config.restart_strategy {
+            RestartStrategy::None => None,
+            RestartStrategy::Luby { base } => {
+                // a zero limit would restart at every failure without exploring anything
+                assert!(base > 0);
+                Some(base * luby(self.restarts + 1))
+            }
+            RestartStrategy::Geometric { base, factor } => {
+                // with factor 1 the limit never grows, and a search which needs more failures never ends
+                assert!(base > 0 && factor > 1.0);
+                let exp = i32::try_from(self.restarts).unwrap_or(i32::MAX);
+                Some((base as f64 * factor.powi(exp)).ceil() as usize)
+            }
+        }
+    }
+
+    // a failure at the current node, the search is unwound to the root once the restart limit is reached
+    // a failure at the root is final, so it never causes a restart
+    fn count_fail(&mut self) {
+        self.stats.borrow_mut().fails += 1;
+        self.fails_since_restart += 1;
+        if self.depth == 1 {
+            return;
+        }
+        if let Some(limit) = self.restart_limit {
+            if self.fails_since_restart >= limit {
+                self.restarting = true;
+            }
+        }
+    }
+
+    fn limit_reached(&mut self) -> bool {
+        if let (Some(limit), Some(start)) = (self.config.time_limit, self.start) {
+            if start.elapsed() >= limit {
+                self.stats.borrow_mut().timed_out = true;
+                return true;
+            }
+        }
+        if let (Some(limit), Some(last)) = (self.config.proof_time_limit, self.last_improvement) {
+            if last.elapsed() >= limit {
+                return true;
+            }
+        }
+        let stats = self.stats.borrow();
+        if self.config.node_limit.is_some_and(|limit| stats.nodes >= limit) {
+            return true;
+        }
+        if self.config.fail_limit.is_some_and(|limit| stats.fails >= limit) {
+            return true;
+        }
+        false
+    }
+
+    // the cut is posted once and only its bound changes afterwards,
+    // its propagators are queued to run at the next search node
+    fn tighten_objective_cut(&mut self, bound: i64) {
+        self.objective_cut_bound.set(bound);
+        if self.objective_cut.is_none() {
+            let cut_bound = self.objective_cut_bound.clone();
+            let Some(objective) = &self.objective else {
+                return;
+            };
+            let Some(cut) = objective.as_constraint(cut_bound) else {
+                return;
+            };
+            let first = self.propagators.len();
+            cut.create_propagators(self);
+            self.objective_cut_propagators = self.propagators[first..].to_vec();
+            self.objective_cut = Some(cut);
+        }
+        for p in &self.objective_cut_propagators {
+            if !p.borrow().is_queued() {
+                p.borrow_mut().enqueue();
+                self.state.borrow_mut().enqueue(p.clone());
+            }
+        }
+    }
+
+    // every variable is assigned to an accepted solution
+    fn notify_solution(&mut self) {
+        if let Some(callback) = &mut self.config.on_solution {
+            callback(&self.variables);
+        }
+    }
+
     fn search(&mut self) -> bool {
+        self.depth += 1;
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.depth = self.depth;
+            stats.max_depth = usize::max(stats.max_depth, self.depth);
+        }
+        let res = self.search_node();
+        self.depth -= 1;
+        self.stats.borrow_mut().depth = self.depth;
+        res
+    }
+
+    fn search_node(&mut self) -> bool {
+        if self.limit_reached() {
+            self.stopped = true;
+            self.stats.borrow_mut().limit_reached = true;
+            self.leave_open();
+            return false;
+        }
+        self.stats.borrow_mut().nodes += 1;
         #[cfg(debug_assertions)]
         if self.objective.is_some() {
-            println!("current best objective = {}", self.current_min);
+            println!("current best objective = {:?}", self.current_min);
         }
         #[cfg(debug_assertions)]
         for v in self.variables.iter() {
@@ -156,6 +603,7 @@ impl Solver {
                 v.borrow_mut().rollback();
             }
             self.state.borrow_mut().status = 0;
+            self.count_fail();
             return false;
         }
         let mut vars = Vec::new();
@@ -165,34 +613,73 @@ impl Solver {
             }
         }
         if vars.is_empty() {
+            if self.config.max_propagations_per_node.is_some() && !self.check_solution() {
+                // propagation could have stopped before detecting the violation
+                for v in &mut self.variables {
+                    v.borrow_mut().rollback();
+                }
+                return false;
+            }
+            // propagators have to reject every full assignment violating their constraint
+            #[cfg(debug_assertions)]
+            if let Some(i) = self.constraints.iter().position(|c| !c.satisfied()) {
+                panic!(
+                    "all variables are assigned, but constraint #{} is not satisfied",
+                    i
+                );
+            }
             if let Some(objective) = &self.objective {
-                let val = objective.eval();
-                if val < self.current_min {
+                let val = objective.eval_lex();
+                if self.current_min.is_empty() || val < self.current_min {
                     self.current_min = val;
+                    self.last_improvement = Some(Instant::now());
                     if self.best_solution.is_empty() {
                         self.best_solution = vec![0i64; self.variables.len()];
                     }
                     for (i, var) in self.variables.iter().enumerate() {
                         self.best_solution[i] = var.borrow().value();
                     }
+                    let best = &self.best_solution;
+                    self.enumeration.record(|| best.clone());
+                    let cut_bound = match self.current_min[..] {
+                        [best] if best > i64::MIN => Some(best - 1),
+                        _ => None,
+                    };
+                    self.notify_solution();
+                    if let Some(bound) = cut_bound {
+                        self.tighten_objective_cut(bound);
+                    }
+                }
+                for v in &mut self.variables {
+                    v.borrow_mut().rollback();
                 }
+                return true;
+            }
+            self.notify_solution();
+            if !matches!(self.enumeration, Enumeration::Off) {
+                // the search goes on as if the solution was a failure
+                let vars = &self.variables;
+                self.enumeration
+                    .record(|| vars.iter().map(|v| v.borrow().value()).collect());
                 for v in &mut self.variables {
                     v.borrow_mut().rollback();
                 }
+                return false;
             }
             return true;
         }
-        if let Some(objective) = &self.objective {
-            let bound = objective.bound();
-            if bound >= self.current_min {
+        if let Some(bound) = self.node_bound() {
+            // every solution below is componentwise, and so lexicographically, not better than bound
+            if !self.current_min.is_empty() && bound >= self.current_min {
                 for v in &mut self.variables {
                     v.borrow_mut().rollback();
                 }
+                self.count_fail();
                 return false;
             }
         }
-        let v = self.variable_selector.select(vars);
-        let x = self.value_selector.select(v.borrow().domain.as_ref());
+        let v = self.config.variable_selector.select(vars);
+        let x = self.config.value_selector.select(v.borrow().domain.as_ref());
         v.borrow_mut().checkpoint();
         #[cfg(debug_assertions)]
         {
@@ -214,6 +701,16 @@ impl Solver {
         #[cfg(debug_assertions)]
         println!("returned after assignment");
         v.borrow_mut().rollback();
+        if self.stopped || self.restarting {
+            // the other branch is not explored
+            if self.stopped {
+                self.leave_open();
+            }
+            for v in &mut self.variables {
+                v.borrow_mut().rollback();
+            }
+            return found;
+        }
         v.borrow_mut().checkpoint();
         v.borrow_mut().remove(x);
         #[cfg(debug_assertions)]
@@ -240,8 +737,37 @@ impl Solver {
         found
     }
 
+    /// searches for a solution, or for an optimal one if an objective was added
+    /// when true is returned, every variable is assigned to the solution found
+    /// (the best one for optimization), and values can be read until the variables are modified
+    /// when false is returned, domains are restored to the state before the call
     pub fn solve(&mut self) -> bool {
-        let res = self.search();
+        self.stopped = false;
+        self.last_improvement = None;
+        self.start = Some(Instant::now());
+        self.open_bound = i64::MAX;
+        *self.stats.borrow_mut() = SearchStats::default();
+        // the cut of a previous search is switched off until the next improving solution
+        self.objective_cut_bound.set(i64::MAX);
+        self.restarts = 0;
+        let mut res = false;
+        loop {
+            self.restart_limit = self.next_restart_limit();
+            self.fails_since_restart = 0;
+            self.restarting = false;
+            self.enqueue_all();
+            // solutions found before a restart are kept, for satisfaction search stops at the first one
+            res |= self.search();
+            if !self.restarting || self.stopped {
+                break;
+            }
+            self.restarts += 1;
+            self.stats.borrow_mut().restarts = self.restarts;
+        }
+        self.restarting = false;
+        // a satisfaction search returning a solution skipped the rest of the tree
+        let stopped_at_solution = res && self.objective.is_none();
+        self.stats.borrow_mut().whole_tree_explored = !self.stopped && !stopped_at_solution;
         if self.objective.is_some() && res {
             for (i, v) in self.variables.iter_mut().enumerate() {
                 v.borrow_mut().assign(self.best_solution[i]);
@@ -249,6 +775,38 @@ impl Solver {
         }
         res
     }
+    /// every solution for a satisfaction problem, every improving solution for optimization
+    /// a solution holds the values of all variables in the order of their creation
+    /// afterwards domains are in the same state as after solve()
+    pub fn solve_all(&mut self) -> Vec<Vec<i64>> {
+        self.enumeration = Enumeration::Store(Vec::new());
+        self.solve();
+        match std::mem::replace(&mut self.enumeration, Enumeration::Off) {
+            Enumeration::Store(solutions) => solutions,
+            _ => unreachable!(),
+        }
+    }
+    /// number of solutions solve_all() would return, without storing them
+    /// the count is exact if get_stats().whole_tree_explored holds, a limit can stop the search earlier
+    pub fn count_solutions(&mut self) -> usize {
+        self.enumeration = Enumeration::Count(0);
+        self.solve();
+        match std::mem::replace(&mut self.enumeration, Enumeration::Off) {
+            Enumeration::Count(count) => count,
+            _ => unreachable!(),
+        }
+    }
+    /// false if the last solve() was stopped by a limit,
+    /// then a returned solution may be suboptimal, and false does not prove infeasibility
+    pub fn search_completed(&self) -> bool {
+        !self.stopped
+    }
+    /// lower bound of the objective proven by the last solve(), the objective is always minimized
+    /// equals get_objective() when the search completed, otherwise the difference is the optimality gap
+    /// i64::MAX if the search completed without finding a solution
+    pub fn objective_lower_bound(&self) -> i64 {
+        i64::min(self.open_bound, self.get_objective())
+    }
 }
 
 // this function transforms satisfaction problem to minimization problem via binary search
@@ -261,8 +819,9 @@ pub fn binary_search_optimizer(
     mut l: i64,
     mut r: i64,
 ) -> i64 {
-    while r - l > 1 {
-        let mid = (l + r) / 2;
+    // i128 keeps r - l and l + r from overflowing for bounds close to i64::MIN and i64::MAX
+    while (r as i128) - (l as i128) > 1 {
+        let mid = ((l as i128 + r as i128) / 2) as i64;
         let mut solver = create_solver(mid);
         if solver.solve() {
             r = mid;
@@ -0,0 +1,297 @@
+use crate::constraint::Constraint;
+use crate::events::Event;
+use crate::propagator::{Propagator, PropagatorControlBlock};
+use crate::solver::{Solver, SolverState};
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// (vars[0], ..., vars[n - 1]) is one of the allowed tuples
+pub struct TableConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    tuples: Vec<Vec<i64>>,
+}
+
+impl TableConstraint {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, tuples: Vec<Vec<i64>>) -> Self {
+        for t in &tuples {
+            assert!(t.len() == vars.len());
+        }
+        Self { vars, tuples }
+    }
+}
+
+impl Constraint for TableConstraint {
+    fn satisfied(&self) -> bool {
+        let mut values = Vec::with_capacity(self.vars.len());
+        for v in &self.vars {
+            match v.borrow().try_value() {
+                Some(x) => values.push(x),
+                None => {
+                    return false;
+                }
+            }
+        }
+        self.tuples.contains(&values)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(TablePropagator::new(
+            self.vars.clone(),
+            self.tuples.clone(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        Some(Rc::new(RefCell::new(NegativeTableConstraint::new(
+            self.vars.clone(),
+            self.tuples.clone(),
+        ))))
+    }
+}
+
+/// GAC propagator, every value keeps the last tuple found to support it (residue)
+/// and the residue is checked first, before scanning all tuples containing the value
+pub struct TablePropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    tuples: Vec<Vec<i64>>,
+    // supports[i][x] are indices of tuples with vars[i] = x
+    supports: Vec<HashMap<i64, Vec<usize>>>,
+    residues: Vec<HashMap<i64, usize>>,
+}
+
+impl TablePropagator {
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, tuples: Vec<Vec<i64>>, id: usize) -> Self {
+        let mut supports = vec![HashMap::<i64, Vec<usize>>::new(); vars.len()];
+        for (t, tuple) in tuples.iter().enumerate() {
+            for (i, x) in tuple.iter().enumerate() {
+                supports[i].entry(*x).or_default().push(t);
+            }
+        }
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            residues: vec![HashMap::new(); vars.len()],
+            vars,
+            tuples,
+            supports,
+        }
+    }
+
+    fn valid(&self, t: usize) -> bool {
+        self.tuples[t]
+            .iter()
+            .zip(self.vars.iter())
+            .all(|(x, v)| v.borrow().possible(*x))
+    }
+
+    fn has_support(&mut self, i: usize, x: i64) -> bool {
+        if let Some(t) = self.residues[i].get(&x) {
+            if self.valid(*t) {
+                return true;
+            }
+        }
+        let Some(candidates) = self.supports[i].get(&x) else {
+            return false;
+        };
+        let Some(t) = candidates.iter().cloned().find(|t| self.valid(*t)) else {
+            return false;
+        };
+        self.residues[i].insert(x, t);
+        true
+    }
+}
+
+impl Propagator for TablePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        // a removal can take the last support from values checked before it, so repeat until nothing changes
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 0..self.vars.len() {
+                let values: Vec<i64> = self.vars[i].borrow().iter().collect();
+                for x in values {
+                    if self.has_support(i, x) {
+                        continue;
+                    }
+                    if !self.vars[i].borrow_mut().remove(x) {
+                        return;
+                    }
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * self.tuples.len()
+    }
+}
+
+/// (vars[0], ..., vars[n - 1]) is none of the forbidden tuples
+pub struct NegativeTableConstraint {
+    vars: Vec<Rc<RefCell<Variable>>>,
+    tuples: Vec<Vec<i64>>,
+}
+
+impl NegativeTableConstraint {
+    /// tuples with values outside of the current domains can never be matched, so they are dropped
+    pub fn new(vars: Vec<Rc<RefCell<Variable>>>, tuples: Vec<Vec<i64>>) -> Self {
+        for t in &tuples {
+            assert!(t.len() == vars.len());
+        }
+        let tuples = tuples
+            .into_iter()
+            .filter(|t| t.iter().zip(vars.iter()).all(|(x, v)| v.borrow().possible(*x)))
+            .collect();
+        Self { vars, tuples }
+    }
+}
+
+impl Constraint for NegativeTableConstraint {
+    fn satisfied(&self) -> bool {
+        let mut values = Vec::with_capacity(self.vars.len());
+        for v in &self.vars {
+            match v.borrow().try_value() {
+                Some(x) => values.push(x),
+                None => {
+                    return false;
+                }
+            }
+        }
+        !self.tuples.contains(&values)
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        let p = Rc::new(RefCell::new(NegativeTablePropagator::new(
+            self.vars.clone(),
+            self.tuples.clone(),
+            solver.solver_state(),
+            solver.new_propagator_id(),
+        )));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+    }
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        self.vars.clone()
+    }
+
+    fn negation(&self) -> Option<Rc<RefCell<dyn Constraint>>> {
+        Some(Rc::new(RefCell::new(TableConstraint::new(
+            self.vars.clone(),
+            self.tuples.clone(),
+        ))))
+    }
+}
+
+/// prunes a forbidden tuple only when all but one of its values are assigned
+pub struct NegativeTablePropagator {
+    pcb: PropagatorControlBlock,
+    vars: Vec<Rc<RefCell<Variable>>>,
+    tuples: Vec<Vec<i64>>,
+    // failures go here, as an empty tuple is matched without any variables
+    solver_state: Rc<RefCell<SolverState>>,
+}
+
+impl NegativeTablePropagator {
+    pub fn new(
+        vars: Vec<Rc<RefCell<Variable>>>,
+        tuples: Vec<Vec<i64>>,
+        solver_state: Rc<RefCell<SolverState>>,
+        id: usize,
+    ) -> Self {
+        Self {
+            pcb: PropagatorControlBlock::new(id),
+            vars,
+            tuples,
+            solver_state,
+        }
+    }
+}
+
+impl Propagator for NegativeTablePropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        for v in &self.vars {
+            v.borrow_mut()
+                .add_listener(self_pointer.clone(), Event::Modified);
+        }
+    }
+
+    fn propagate(&mut self) {
+        for t in &self.tuples {
+            // the only variable not assigned to its value in t, if there is exactly one
+            let mut free = None;
+            let mut matched = true;
+            for (i, (x, v)) in t.iter().zip(self.vars.iter()).enumerate() {
+                let v = v.borrow();
+                if !v.possible(*x) {
+                    matched = false;
+                    break;
+                }
+                if !v.is_assigned() {
+                    if free.is_some() {
+                        matched = false;
+                        break;
+                    }
+                    free = Some(i);
+                }
+            }
+            if !matched {
+                continue;
+            }
+            match free {
+                Some(i) => {
+                    self.vars[i].borrow_mut().remove(t[i]);
+                }
+                None => {
+                    self.solver_state.borrow_mut().fail();
+                    return;
+                }
+            }
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    fn estimated_cost(&self) -> usize {
+        self.vars.len() * self.tuples.len()
+    }
+}
@@ -0,0 +1,87 @@
+//! helpers for testing propagators, enabled by the `testing` feature
+
+use crate::constraint::Constraint;
+use crate::solver::Solver;
+use crate::variable::Variable;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// upper bound on the number of tuples check_gac is willing to enumerate
+pub const MAX_TUPLES: u64 = 1 << 20;
+
+// calls f for every assignment of vars within their current domains
+fn for_each_tuple(vars: &[Rc<RefCell<Variable>>], f: &mut impl FnMut()) {
+    let Some((first, rest)) = vars.split_first() else {
+        f();
+        return;
+    };
+    let values: Vec<i64> = first.borrow().iter().collect();
+    for x in values {
+        first.borrow_mut().checkpoint();
+        first.borrow_mut().assign(x);
+        for_each_tuple(rest, f);
+        first.borrow_mut().rollback();
+    }
+}
+
+/// checks propagators of the constraint against brute force on the current domains of its variables
+/// the constraint is added to the solver, and propagated at the root
+/// with exact = true, domains have to be exactly the GAC domains (values having a supporting solution),
+/// otherwise they only have to contain them, so weaker propagators can be checked too
+/// panics on mismatch
+pub fn check_gac(solver: &mut Solver, constraint: Box<dyn Constraint>, exact: bool) {
+    let mut vars = Vec::<Rc<RefCell<Variable>>>::new();
+    for v in constraint.variables() {
+        if !vars.iter().any(|u| Rc::ptr_eq(u, &v)) {
+            vars.push(v);
+        }
+    }
+    let tuples = vars
+        .iter()
+        .fold(1u64, |acc, v| acc.saturating_mul(v.borrow().size()));
+    assert!(tuples <= MAX_TUPLES, "{} tuples are too many to enumerate", tuples);
+    let mut supported: Vec<Vec<i64>> = vec![Vec::new(); vars.len()];
+    for_each_tuple(&vars, &mut || {
+        if constraint.satisfied() {
+            for (v, values) in vars.iter().zip(supported.iter_mut()) {
+                let x = v.borrow().value();
+                if !values.contains(&x) {
+                    values.push(x);
+                }
+            }
+        }
+    });
+    for values in supported.iter_mut() {
+        values.sort();
+    }
+    solver.add_constraint(constraint);
+    if !solver.propagate_all() {
+        assert!(
+            supported.iter().any(|values| values.is_empty()),
+            "propagation failed, but a solution exists"
+        );
+        return;
+    }
+    for (v, expected) in vars.iter().zip(supported.iter()) {
+        let actual: Vec<i64> = v.borrow().iter().collect();
+        let missing: Vec<i64> = expected
+            .iter()
+            .cloned()
+            .filter(|x| !actual.contains(x))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "values {:?} of {} have support, but were removed",
+            missing,
+            v.borrow().name
+        );
+        if exact && !expected.is_empty() {
+            assert_eq!(
+                &actual,
+                expected,
+                "domain of {} is not GAC",
+                v.borrow().name
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::domain::Domain;
+use std::cell::Cell;
 
 pub trait ValueSelector {
     fn select(&self, dom: &dyn Domain) -> i64;
@@ -19,3 +20,89 @@ impl ValueSelector for MaxValueSelector {
         dom.get_ub()
     }
 }
+
+/// picks the median of the domain, which is present even if the domain has holes
+pub struct MedianValueSelector {}
+
+impl ValueSelector for MedianValueSelector {
+    fn select(&self, dom: &dyn Domain) -> i64 {
+        dom.get_median()
+    }
+}
+
+/// picks the first value of the priority list present in the domain,
+/// falls back to the smallest value if there is none
+pub struct ValueOrderValueSelector {
+    priority: Vec<i64>,
+}
+
+impl ValueOrderValueSelector {
+    pub fn new(priority: Vec<i64>) -> Self {
+        Self { priority }
+    }
+}
+
+impl ValueSelector for ValueOrderValueSelector {
+    fn select(&self, dom: &dyn Domain) -> i64 {
+        for x in &self.priority {
+            if dom.possible(*x) {
+                return *x;
+            }
+        }
+        dom.get_lb()
+    }
+}
+
+/// tries values in the order returned by the closure for the current domain,
+/// the search assigns the first value and removes it on backtracking, so the next call
+/// gives the next value of the order as long as the order does not depend on the removed values
+/// falls back to the smallest value if the order has no value of the domain
+pub struct CustomOrderValueSelector<F: Fn(&dyn Domain) -> Vec<i64>> {
+    order: F,
+}
+
+impl<F: Fn(&dyn Domain) -> Vec<i64>> CustomOrderValueSelector<F> {
+    pub fn new(order: F) -> Self {
+        Self { order }
+    }
+}
+
+impl<F: Fn(&dyn Domain) -> Vec<i64>> ValueSelector for CustomOrderValueSelector<F> {
+    fn select(&self, dom: &dyn Domain) -> i64 {
+        (self.order)(dom)
+            .into_iter()
+            .find(|x| dom.possible(*x))
+            .unwrap_or_else(|| dom.get_lb())
+    }
+}
+
+// advances the linear congruential generator, returns a number in 0..m
+// high bits are used, as low bits of an lcg with power of two modulus have short periods
+pub(crate) fn next_random(state: &Cell<u64>, m: u64) -> u64 {
+    let x = state
+        .get()
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    state.set(x);
+    (x >> 33) % m
+}
+
+/// picks a uniformly random value of the domain
+/// the sequence of choices is determined by the seed
+pub struct RandomValueSelector {
+    state: Cell<u64>,
+}
+
+impl RandomValueSelector {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+}
+
+impl ValueSelector for RandomValueSelector {
+    fn select(&self, dom: &dyn Domain) -> i64 {
+        dom.nth_present(next_random(&self.state, dom.size()))
+    }
+}
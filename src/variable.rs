@@ -1,6 +1,7 @@
 use crate::bitset::BitsetDomain;
 use crate::domain::{Domain, DomainState, SmallDomain};
 use crate::events::{event_index, Event, N_EVENTS};
+use crate::interval::IntervalDomain;
 use crate::propagator::Propagator;
 use crate::solver::SolverState;
 use std::boxed::Box;
@@ -8,29 +9,115 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// propagators listening to one event, keyed by propagator id
+/// listeners are drained in the order they were added, so propagation order does not depend on hashing
+/// memory is proportional to the number of listeners, not to the largest id
+#[derive(Default)]
+pub struct ListenerSet {
+    listeners: Vec<Rc<RefCell<dyn Propagator>>>,
+    // index in listeners of each id
+    position: HashMap<usize, usize>,
+}
+
+impl ListenerSet {
+    pub fn insert(&mut self, id: usize, listener: Rc<RefCell<dyn Propagator>>) {
+        match self.position.get(&id) {
+            Some(pos) => self.listeners[*pos] = listener,
+            None => {
+                self.position.insert(id, self.listeners.len());
+                self.listeners.push(listener);
+            }
+        }
+    }
+    pub fn contains(&self, id: usize) -> bool {
+        self.position.contains_key(&id)
+    }
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+    pub fn drain(&mut self) -> Vec<Rc<RefCell<dyn Propagator>>> {
+        self.position.clear();
+        std::mem::take(&mut self.listeners)
+    }
+}
+
+/// representation of a variable's domain
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DomainKind {
+    /// SmallDomain for at most 64 values, BitsetDomain otherwise
+    Auto,
+    Bitset,
+    /// bounds only until a value between them is removed, see IntervalDomain
+    /// never picked by Auto, as a removal in the middle costs as much as a bitset of the whole range
+    Interval,
+}
+
 pub struct Variable {
     pub domain: Box<dyn Domain>,
-    pub listeners: [HashMap<usize, Rc<RefCell<dyn Propagator>>>; N_EVENTS],
+    pub listeners: [ListenerSet; N_EVENTS],
     pub solver_state: Rc<RefCell<SolverState>>,
     pub name: String,
+    /// number of failures of constraints involving the variable, updated by the solver
+    pub weight: u64,
 }
 
 impl Variable {
     pub fn new(solver_state: Rc<RefCell<SolverState>>, lb: i64, ub: i64, name: String) -> Self {
-        let domain: Box<dyn Domain> = match ub - lb <= 63 {
-            true => Box::new(SmallDomain::new(solver_state.clone(), lb, ub)),
-            false => Box::new(BitsetDomain::new(solver_state.clone(), lb, ub)),
+        Self::with_kind(solver_state, lb, ub, DomainKind::Auto, name)
+    }
+    pub fn with_kind(
+        solver_state: Rc<RefCell<SolverState>>,
+        lb: i64,
+        ub: i64,
+        kind: DomainKind,
+        name: String,
+    ) -> Self {
+        let size = (ub as i128) - (lb as i128) + 1;
+        let domain: Box<dyn Domain> = match kind {
+            DomainKind::Auto if size <= 64 => {
+                Box::new(SmallDomain::new(solver_state.clone(), lb, ub))
+            }
+            DomainKind::Auto | DomainKind::Bitset => {
+                Box::new(BitsetDomain::new(solver_state.clone(), lb, ub))
+            }
+            DomainKind::Interval => Box::new(IntervalDomain::new(solver_state.clone(), lb, ub)),
         };
         Self {
             domain,
             listeners: Default::default(),
             solver_state,
             name,
+            weight: 0,
+        }
+    }
+    /// variable with the union of the closed intervals [l, r] in ranges as domain
+    pub fn with_ranges(
+        solver_state: Rc<RefCell<SolverState>>,
+        ranges: &[(i64, i64)],
+        name: String,
+    ) -> Self {
+        Self {
+            domain: Box::new(BitsetDomain::from_ranges(solver_state.clone(), ranges)),
+            listeners: Default::default(),
+            solver_state,
+            name,
+            weight: 0,
         }
     }
     pub fn assign(&mut self, x: i64) -> bool {
+        let old_lb = self.domain.get_lb();
+        let old_ub = self.domain.get_ub();
         match self.domain.assign(x) {
             DomainState::Modified => {
+                if old_lb != x {
+                    self.notify_listeners(Event::LowerBound);
+                }
+                if old_ub != x {
+                    self.notify_listeners(Event::UpperBound);
+                }
                 self.notify_listeners(Event::Assigned);
                 self.notify_listeners(Event::Modified);
                 return true;
@@ -61,6 +148,7 @@ impl Variable {
         }
         match self.domain.remove(x) {
             DomainState::Modified => {
+                self.notify_if_assigned();
                 self.notify_listeners(Event::Modified);
                 return true;
             }
@@ -78,10 +166,14 @@ impl Variable {
     pub fn get_ub(&self) -> i64 {
         self.domain.get_ub()
     }
+    pub fn get_median(&self) -> i64 {
+        self.domain.get_median()
+    }
     pub fn set_lb(&mut self, x: i64) -> bool {
         match self.domain.set_lb(x) {
             DomainState::Modified => {
                 self.notify_listeners(Event::LowerBound);
+                self.notify_if_assigned();
                 self.notify_listeners(Event::Modified);
                 return true;
             }
@@ -97,6 +189,7 @@ impl Variable {
         match self.domain.set_ub(x) {
             DomainState::Modified => {
                 self.notify_listeners(Event::UpperBound);
+                self.notify_if_assigned();
                 self.notify_listeners(Event::Modified);
                 return true;
             }
@@ -108,6 +201,26 @@ impl Variable {
             }
         }
     }
+    /// restricts domain to [lb, ub], notifying every event at most once
+    pub fn tighten(&mut self, lb: i64, ub: i64) -> bool {
+        let old_lb = self.domain.get_lb();
+        let old_ub = self.domain.get_ub();
+        match self.domain.tighten(lb, ub) {
+            DomainState::Modified => {
+                if self.domain.get_lb() != old_lb {
+                    self.notify_listeners(Event::LowerBound);
+                }
+                if self.domain.get_ub() != old_ub {
+                    self.notify_listeners(Event::UpperBound);
+                }
+                self.notify_if_assigned();
+                self.notify_listeners(Event::Modified);
+                true
+            }
+            DomainState::Failed => false,
+            _ => true,
+        }
+    }
     pub fn value(&self) -> i64 {
         let lb = self.domain.get_lb();
         let ub = self.domain.get_ub();
@@ -117,13 +230,26 @@ impl Variable {
             return lb;
         }
     }
+    pub fn try_value(&self) -> Option<i64> {
+        if self.domain.is_assigned() {
+            Some(self.domain.get_lb())
+        } else {
+            None
+        }
+    }
+    // after a modification, the domain could have been left with a single value
+    fn notify_if_assigned(&mut self) {
+        if self.domain.is_assigned() {
+            self.notify_listeners(Event::Assigned);
+        }
+    }
     pub fn add_listener(&mut self, listener: Rc<RefCell<dyn Propagator>>, event: Event) {
         let id = event_index(&event);
         let list_id = listener.borrow().get_id();
         self.listeners[id].insert(list_id, listener);
     }
     pub fn notify_listeners(&mut self, event: Event) {
-        for (_, listener) in self.listeners[event_index(&event)].drain() {
+        for listener in self.listeners[event_index(&event)].drain() {
             if let Ok(mut ref_mut) = listener.try_borrow_mut() {
                 ref_mut.new_event();
             } else {
@@ -150,3 +276,99 @@ impl Variable {
         self.domain.size()
     }
 }
+
+// bits of the bitmap for values from, ..., from + 63
+fn bitmap_word(start: i64, words: &[u64], from: i64) -> u64 {
+    let offset = from - start;
+    let q = offset.div_euclid(64);
+    let r = offset.rem_euclid(64) as u32;
+    let word = |i: i64| {
+        if i < 0 || i >= words.len() as i64 {
+            0
+        } else {
+            words[i as usize]
+        }
+    };
+    if r == 0 {
+        word(q)
+    } else {
+        (word(q) >> r) | (word(q + 1) << (64 - r))
+    }
+}
+
+// size of intersection of domains, stops counting once it reaches limit
+fn overlap(a: &Variable, b: &Variable, limit: u64) -> u64 {
+    if a.get_ub() < b.get_lb() || b.get_ub() < a.get_lb() {
+        return 0;
+    }
+    let mut ans = 0;
+    if let (Some((sa, wa)), Some((sb, wb))) = (a.domain.bitmap(), b.domain.bitmap()) {
+        for (i, w) in wa.iter().enumerate() {
+            if *w == 0 {
+                continue;
+            }
+            ans += (w & bitmap_word(sb, wb, sa + 64 * i as i64)).count_ones() as u64;
+            if ans >= limit {
+                break;
+            }
+        }
+    } else {
+        let (small, large) = if a.size() <= b.size() { (a, b) } else { (b, a) };
+        for x in small.iter() {
+            if large.possible(x) {
+                ans += 1;
+                if ans >= limit {
+                    break;
+                }
+            }
+        }
+    }
+    ans
+}
+
+/// number of values present in both domains
+pub fn domain_overlap(a: &Variable, b: &Variable) -> u64 {
+    overlap(a, b, u64::MAX)
+}
+
+/// checks that no value is present in both domains
+pub fn domains_disjoint(a: &Variable, b: &Variable) -> bool {
+    overlap(a, b, 1) == 0
+}
+
+/// variable with domain inside {0, 1}, 1 is true
+#[derive(Clone)]
+pub struct BoolVar(Rc<RefCell<Variable>>);
+
+impl BoolVar {
+    /// panics if var can take a value other than 0 or 1
+    pub fn new(var: Rc<RefCell<Variable>>) -> Self {
+        {
+            let v = var.borrow();
+            assert!(
+                v.get_lb() >= 0 && v.get_ub() <= 1,
+                "{} is not a boolean variable",
+                v.name
+            );
+        }
+        Self(var)
+    }
+    pub fn var(&self) -> Rc<RefCell<Variable>> {
+        self.0.clone()
+    }
+    pub fn is_fixed(&self) -> bool {
+        self.0.borrow().is_assigned()
+    }
+    pub fn is_true(&self) -> bool {
+        self.0.borrow().try_value() == Some(1)
+    }
+    pub fn is_false(&self) -> bool {
+        self.0.borrow().try_value() == Some(0)
+    }
+}
+
+impl From<BoolVar> for Rc<RefCell<Variable>> {
+    fn from(b: BoolVar) -> Self {
+        b.0
+    }
+}
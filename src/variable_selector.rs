@@ -1,5 +1,6 @@
+use crate::value_selector::next_random;
 use crate::variable::Variable;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 pub trait VariableSelector {
@@ -30,3 +31,114 @@ impl VariableSelector for FirstFailVariableSelector {
         vars[pos].clone()
     }
 }
+
+/// picks the variable with the smallest ratio of domain size to weight + 1,
+/// the weight counts failures of constraints involving the variable
+/// weights are maintained by the solver for propagators created by Solver::add_constraint,
+/// so failures of propagators added directly with Solver::add_propagator are not counted
+/// ties are broken by the order of variables
+pub struct DomWDegVariableSelector {}
+
+impl VariableSelector for DomWDegVariableSelector {
+    fn select(&self, vars: Vec<Rc<RefCell<Variable>>>) -> Rc<RefCell<Variable>> {
+        let key = |v: &Rc<RefCell<Variable>>| {
+            let v = v.borrow();
+            (v.size() as u128, v.weight as u128 + 1)
+        };
+        let mut pos = 0;
+        let (mut best_size, mut best_weight) = key(&vars[0]);
+        for (i, v) in vars.iter().enumerate().skip(1) {
+            let (size, weight) = key(v);
+            // size / weight < best_size / best_weight
+            if size * best_weight < best_size * weight {
+                pos = i;
+                best_size = size;
+                best_weight = weight;
+            }
+        }
+        vars[pos].clone()
+    }
+}
+
+/// picks a uniformly random variable
+/// the sequence of choices is determined by the seed
+pub struct RandomVariableSelector {
+    state: Cell<u64>,
+}
+
+impl RandomVariableSelector {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+}
+
+impl VariableSelector for RandomVariableSelector {
+    fn select(&self, vars: Vec<Rc<RefCell<Variable>>>) -> Rc<RefCell<Variable>> {
+        vars[next_random(&self.state, vars.len() as u64) as usize].clone()
+    }
+}
+
+/// first-fail with ties between smallest domains broken uniformly at random
+/// the sequence of choices is determined by the seed
+pub struct FirstFailRandomTieVariableSelector {
+    state: Cell<u64>,
+}
+
+impl FirstFailRandomTieVariableSelector {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+}
+
+impl VariableSelector for FirstFailRandomTieVariableSelector {
+    fn select(&self, vars: Vec<Rc<RefCell<Variable>>>) -> Rc<RefCell<Variable>> {
+        let mut pos = 0;
+        let mut best_size = vars[0].borrow().size();
+        // number of variables of size best_size seen so far, each of them is kept with equal probability
+        let mut ties = 1;
+        for (i, v) in vars.iter().enumerate().skip(1) {
+            let size = v.borrow().size();
+            if size < best_size {
+                pos = i;
+                best_size = size;
+                ties = 1;
+            } else if size == best_size {
+                ties += 1;
+                if next_random(&self.state, ties) == 0 {
+                    pos = i;
+                }
+            }
+        }
+        vars[pos].clone()
+    }
+}
+
+/// picks the first variable which can take the highest priority value still present in some domain
+/// use with ValueOrderValueSelector to commit to values in a fixed global order,
+/// e.g. in graph coloring it tries color priority[0] everywhere first
+pub struct ValueOrderVariableSelector {
+    priority: Vec<i64>,
+}
+
+impl ValueOrderVariableSelector {
+    pub fn new(priority: Vec<i64>) -> Self {
+        Self { priority }
+    }
+}
+
+impl VariableSelector for ValueOrderVariableSelector {
+    fn select(&self, vars: Vec<Rc<RefCell<Variable>>>) -> Rc<RefCell<Variable>> {
+        for x in &self.priority {
+            for v in &vars {
+                if v.borrow().possible(*x) {
+                    return v.clone();
+                }
+            }
+        }
+        vars[0].clone()
+    }
+}
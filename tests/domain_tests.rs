@@ -0,0 +1,233 @@
+use ezcp::bitset::BitsetDomain;
+use ezcp::domain::{Domain, DomainState, SmallDomain};
+use ezcp::interval::IntervalDomain;
+use ezcp::solver::SolverState;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn both_domains(lb: i64, ub: i64) -> Vec<Box<dyn Domain>> {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    vec![
+        Box::new(SmallDomain::new(solver_state.clone(), lb, ub)),
+        Box::new(BitsetDomain::new(solver_state, lb, ub)),
+    ]
+}
+
+#[test]
+fn test_holes() {
+    for mut d in both_domains(-10, 40) {
+        assert!(d.holes().is_empty());
+        for x in [-8, 0, 1, 2, 30, 39] {
+            d.remove(x);
+        }
+        d.set_lb(-9);
+        assert_eq!(d.holes(), vec![(-8, -8), (0, 2), (30, 30), (39, 39)]);
+        d.set_ub(38);
+        assert_eq!(d.holes(), vec![(-8, -8), (0, 2), (30, 30)]);
+    }
+}
+
+#[test]
+fn test_nth_present() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let mut domains = both_domains(-5, 50);
+    domains.push(Box::new(BitsetDomain::new(solver_state, -100, 300)));
+    for mut d in domains {
+        let ub = d.get_ub();
+        for x in (-100..=300).filter(|x| x % 7 == 0 || x % 5 == 1) {
+            d.remove(x);
+        }
+        d.set_lb(-3);
+        d.set_ub(ub - 2);
+        let values: Vec<i64> = d.iter().collect();
+        assert_eq!(values.len() as u64, d.size());
+        for (k, x) in values.iter().enumerate() {
+            assert_eq!(d.nth_present(k as u64), *x);
+        }
+    }
+}
+
+// DomainState has no Debug, so states are compared through this
+fn state_name(s: DomainState) -> &'static str {
+    match s {
+        DomainState::Same => "same",
+        DomainState::Modified => "modified",
+        DomainState::Failed => "failed",
+    }
+}
+
+#[test]
+fn test_get_median() {
+    for mut d in both_domains(0, 20) {
+        assert_eq!(d.get_median(), 10);
+        // the middle of [lb, ub] is gone
+        for x in 5..=15 {
+            d.remove(x);
+        }
+        d.remove(20);
+        // 0..4 and 16..19 are left
+        assert_eq!(d.get_median(), 4);
+        // 2..4 and 16..19
+        d.set_lb(2);
+        assert_eq!(d.get_median(), 16);
+        d.set_lb(17);
+        assert_eq!(d.get_median(), 18);
+    }
+}
+
+#[test]
+fn test_domains_agree() {
+    let mut seed: u64 = 99;
+    let mut rand = |m: u64| {
+        seed = (seed * 5 + 11) % (1 << 31);
+        (seed >> 8) % m
+    };
+    for _ in 0..300 {
+        let lb = rand(80) as i64 - 70;
+        let ub = lb + rand(64) as i64;
+        let mut domains = both_domains(lb, ub);
+        domains.push(Box::new(IntervalDomain::new(
+            Rc::new(RefCell::new(SolverState::new())),
+            lb,
+            ub,
+        )));
+        let mut depth = 0;
+        for step in 0..60 {
+            // mostly values around the domain, sometimes right at or past the end of the 64 value window
+            let x = match rand(4) {
+                0 => lb + 62 + rand(4) as i64,
+                _ => lb - 3 + rand((ub - lb + 7) as u64) as i64,
+            };
+            let op = rand(7);
+            if op == 5 {
+                for d in domains.iter_mut() {
+                    d.checkpoint();
+                }
+                depth += 1;
+                continue;
+            }
+            if op == 6 {
+                if depth > 0 {
+                    for d in domains.iter_mut() {
+                        d.rollback();
+                    }
+                    depth -= 1;
+                }
+                continue;
+            }
+            let states: Vec<&str> = domains
+                .iter_mut()
+                .map(|d| {
+                    state_name(match op {
+                        0 => d.remove(x),
+                        1 => d.set_lb(x),
+                        2 => d.set_ub(x),
+                        3 => d.assign(x),
+                        _ => d.tighten(x, x + 3),
+                    })
+                })
+                .collect();
+            let context = format!("[{}, {}], step {}, op {} with {}", lb, ub, step, op, x);
+            for state in &states[1..] {
+                assert_eq!(states[0], *state, "{}", context);
+            }
+            if states[0] == "failed" {
+                // the solver always rolls back after a failure
+                if depth == 0 {
+                    break;
+                }
+                for d in domains.iter_mut() {
+                    d.rollback();
+                }
+                depth -= 1;
+            }
+            let values: Vec<Vec<i64>> = domains.iter().map(|d| d.iter().collect()).collect();
+            for (d, vals) in domains.iter().zip(values.iter()).skip(1) {
+                assert_eq!(values[0], *vals, "{}", context);
+                assert_eq!(domains[0].size(), d.size(), "{}", context);
+                assert_eq!(domains[0].get_lb(), d.get_lb(), "{}", context);
+                assert_eq!(domains[0].get_ub(), d.get_ub(), "{}", context);
+                assert_eq!(domains[0].is_assigned(), d.is_assigned(), "{}", context);
+                for y in lb - 2..lb + 66 {
+                    assert_eq!(domains[0].possible(y), d.possible(y), "{}", context);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_small_domain_rollback() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let mut d = SmallDomain::new(solver_state, 0, 9);
+    d.checkpoint();
+    d.remove(4);
+    d.set_lb(2);
+    d.checkpoint();
+    // a checkpoint without changes
+    d.checkpoint();
+    d.rollback();
+    d.set_ub(6);
+    d.remove(5);
+    assert_eq!(d.iter().collect::<Vec<_>>(), vec![2, 3, 6]);
+    d.checkpoint();
+    d.assign(3);
+    d.rollback();
+    assert_eq!(d.iter().collect::<Vec<_>>(), vec![2, 3, 6]);
+    assert_eq!((d.get_lb(), d.get_ub()), (2, 6));
+    d.rollback();
+    assert_eq!(d.iter().collect::<Vec<_>>(), vec![2, 3, 5, 6, 7, 8, 9]);
+    assert_eq!((d.get_lb(), d.get_ub()), (2, 9));
+    d.rollback();
+    assert_eq!(d.size(), 10);
+    assert_eq!((d.get_lb(), d.get_ub()), (0, 9));
+}
+
+#[test]
+fn test_interval_domain() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let mut d = IntervalDomain::new(solver_state, 0, 1_000_000_000_000);
+    assert_eq!(d.size(), 1_000_000_000_001);
+    assert!(d.bitmap().is_none());
+    d.checkpoint();
+    d.set_lb(10);
+    d.remove(10);
+    assert_eq!(d.get_lb(), 11);
+    d.set_ub(100);
+    d.checkpoint();
+    // the domain gets holes only now
+    d.remove(50);
+    assert!(!d.possible(50) && d.bitmap().is_some());
+    assert_eq!(d.holes(), vec![(50, 50)]);
+    assert_eq!(d.size(), 89);
+    d.rollback();
+    assert!(d.possible(50) && d.bitmap().is_none());
+    assert_eq!((d.get_lb(), d.get_ub()), (11, 100));
+    d.rollback();
+    assert_eq!((d.get_lb(), d.get_ub()), (0, 1_000_000_000_000));
+    let d = IntervalDomain::new(
+        Rc::new(RefCell::new(SolverState::new())),
+        i64::MIN,
+        i64::MAX,
+    );
+    assert_eq!(d.size(), u64::MAX);
+}
+
+#[test]
+fn test_bitset_from_ranges() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // unordered and overlapping ranges, one of them spans several words
+    let mut d = BitsetDomain::from_ranges(solver_state, &[(200, 330), (-3, 1), (5, 5), (320, 340)]);
+    assert_eq!(d.get_lb(), -3);
+    assert_eq!(d.get_ub(), 340);
+    assert_eq!(d.size(), 5 + 1 + 141);
+    assert_eq!(d.holes(), vec![(2, 4), (6, 199)]);
+    assert!(d.possible(5) && !d.possible(100));
+    let values: Vec<i64> = d.iter().take(7).collect();
+    assert_eq!(values, vec![-3, -2, -1, 0, 1, 5, 200]);
+    d.set_lb(2);
+    assert_eq!(d.get_lb(), 5);
+    d.remove(5);
+    assert_eq!(d.get_lb(), 200);
+}
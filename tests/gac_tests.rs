@@ -0,0 +1,564 @@
+use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::arithmetic::{
+    AbsConstraint, MaximumConstraint, MinimumConstraint, SumAbsDeviationConstraint, TimesConstraint,
+};
+use ezcp::binpacking::VarBinPackingConstraint;
+use ezcp::channel::InverseConstraint;
+use ezcp::config::Config;
+use ezcp::count::{
+    AmongConstraint, AtLeastConstraint, AtMostConstraint, CountConstraint, NValueConstraint,
+};
+use ezcp::gcc::{BoundedGccConstraint, GlobalCardinalityConstraint};
+use ezcp::graph::CircuitConstraint;
+use ezcp::knapsack::KnapsackConstraint;
+use ezcp::lex::{LexLessConstraint, LexLessEqConstraint, ValuePrecedenceConstraint};
+use ezcp::linear::LinearInequalityConstraint;
+use ezcp::logic::XorConstraint;
+use ezcp::regular::RegularConstraint;
+use ezcp::scheduling::{CumulativeConstraint, DiffnConstraint, DisjunctiveConstraint};
+use ezcp::solver::Solver;
+use ezcp::table::NegativeTableConstraint;
+use ezcp::testing::check_gac;
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::Variable;
+use ezcp::variable_selector::FirstFailVariableSelector;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+fn new_solver() -> Solver {
+    Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    )
+}
+
+// deterministic generator of numbers in 0..m
+fn lcg(mut seed: u64) -> impl FnMut(u64) -> u64 {
+    move |m: u64| {
+        seed = (seed * 5 + 11) % (1 << 31);
+        (seed >> 8) % m
+    }
+}
+
+// variables with random subsets of {0, ..., 4} as domains
+fn random_vars(
+    solver: &mut Solver,
+    n: usize,
+    rand: &mut impl FnMut(u64) -> u64,
+) -> Vec<Rc<RefCell<Variable>>> {
+    (0..n)
+        .map(|i| {
+            let var = solver.new_variable(0, 4, format!("x_{}", i));
+            let keep = rand(5) as i64;
+            for val in 0..5 {
+                if val != keep && rand(2) == 0 {
+                    var.borrow_mut().remove(val);
+                }
+            }
+            var
+        })
+        .collect()
+}
+
+#[test]
+fn test_alldifferent_gac() {
+    let mut rand = lcg(23);
+    for _ in 0..100 {
+        let n = 2 + rand(4) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        check_gac(
+            &mut solver,
+            Box::new(AllDifferentConstraint::new(vars)),
+            true,
+        );
+    }
+}
+
+#[test]
+fn test_linear_sound() {
+    let mut rand = lcg(5);
+    for _ in 0..100 {
+        let n = 2 + rand(3) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let a = (0..n).map(|_| rand(7) as i64 - 3).collect();
+        let b = rand(9) as i64 - 4;
+        // bounds propagation, so only soundness is checked
+        check_gac(
+            &mut solver,
+            Box::new(LinearInequalityConstraint::new(vars, a, b)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_negative_table_sound() {
+    let mut rand = lcg(41);
+    for _ in 0..100 {
+        let n = 2 + rand(2) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let tuples = (0..rand(10))
+            .map(|_| (0..n).map(|_| rand(5) as i64).collect())
+            .collect();
+        // forward checking only, so only soundness is checked
+        check_gac(
+            &mut solver,
+            Box::new(NegativeTableConstraint::new(vars, tuples)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_regular_gac() {
+    let mut rand = lcg(77);
+    for _ in 0..100 {
+        let n = 1 + rand(4) as usize;
+        let states = 1 + rand(4) as usize;
+        // alphabet {0, 1, 2}, larger values in the domains have no transition
+        let transitions = (0..states)
+            .map(|_| (0..3).map(|_| rand(states as u64) as usize).collect())
+            .collect();
+        let accepting = (0..states).filter(|_| rand(2) == 0).collect();
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        check_gac(
+            &mut solver,
+            Box::new(RegularConstraint::new(vars, transitions, 0, accepting)),
+            true,
+        );
+    }
+}
+
+#[test]
+fn test_cumulative_sound() {
+    let mut rand = lcg(13);
+    for _ in 0..200 {
+        let n = 2 + rand(3) as usize;
+        let mut solver = new_solver();
+        let starts = random_vars(&mut solver, n, &mut rand);
+        let durations = (0..n).map(|_| rand(4) as i64).collect();
+        let demands = (0..n).map(|_| rand(4) as i64).collect();
+        let capacity = 1 + rand(4) as i64;
+        // bounds reasoning on mandatory parts only
+        check_gac(
+            &mut solver,
+            Box::new(CumulativeConstraint::new(starts, durations, demands, capacity)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_disjunctive_sound() {
+    let mut rand = lcg(29);
+    for _ in 0..200 {
+        let n = 2 + rand(3) as usize;
+        let mut solver = new_solver();
+        let starts = random_vars(&mut solver, n, &mut rand);
+        let durations = (0..n).map(|_| rand(4) as i64).collect();
+        // bounds reasoning only
+        check_gac(
+            &mut solver,
+            Box::new(DisjunctiveConstraint::new(starts, durations)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_circuit_sound() {
+    let mut rand = lcg(3);
+    for _ in 0..200 {
+        let n = 1 + rand(5) as usize;
+        let mut solver = new_solver();
+        let succ = random_vars(&mut solver, n, &mut rand);
+        check_gac(&mut solver, Box::new(CircuitConstraint::new(succ)), false);
+    }
+}
+
+#[test]
+fn test_alldifferent_bounds_sound() {
+    let mut rand = lcg(61);
+    for _ in 0..100 {
+        let n = 2 + rand(4) as usize;
+        let mut solver = Solver::with_config(Config {
+            alldifferent_ac_max_vertices: Some(0),
+            ..Config::default()
+        });
+        let vars = random_vars(&mut solver, n, &mut rand);
+        check_gac(
+            &mut solver,
+            Box::new(AllDifferentConstraint::new(vars)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_alldifferent_bounds_exact() {
+    // on interval domains the bounds have to be the smallest and largest supported values
+    let mut rand = lcg(109);
+    for _ in 0..300 {
+        let n = 2 + rand(4) as usize;
+        let intervals: Vec<(i64, i64)> = (0..n)
+            .map(|_| {
+                let lb = rand(7) as i64;
+                (lb, lb + rand(4) as i64)
+            })
+            .collect();
+        let mut supported = vec![(i64::MAX, i64::MIN); n];
+        let mut tuple = vec![0; n];
+        fn extend(
+            k: usize,
+            intervals: &[(i64, i64)],
+            tuple: &mut Vec<i64>,
+            supported: &mut Vec<(i64, i64)>,
+        ) {
+            if k == intervals.len() {
+                for (s, x) in supported.iter_mut().zip(tuple.iter()) {
+                    *s = (i64::min(s.0, *x), i64::max(s.1, *x));
+                }
+                return;
+            }
+            for x in intervals[k].0..=intervals[k].1 {
+                if !tuple[..k].contains(&x) {
+                    tuple[k] = x;
+                    extend(k + 1, intervals, tuple, supported);
+                }
+            }
+        }
+        extend(0, &intervals, &mut tuple, &mut supported);
+        let mut solver = Solver::with_config(Config {
+            alldifferent_ac_max_vertices: Some(0),
+            ..Config::default()
+        });
+        let vars: Vec<_> = intervals
+            .iter()
+            .enumerate()
+            .map(|(i, (lb, ub))| solver.new_variable(*lb, *ub, format!("x_{}", i)))
+            .collect();
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+        if !solver.propagate_all() {
+            assert!(supported[0].0 > supported[0].1, "{:?}", intervals);
+            continue;
+        }
+        for (v, s) in vars.iter().zip(supported.iter()) {
+            let v = v.borrow();
+            assert_eq!((v.get_lb(), v.get_ub()), *s, "{:?}", intervals);
+        }
+    }
+}
+
+#[test]
+fn test_count_sound() {
+    let mut rand = lcg(8);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let mut vars = random_vars(&mut solver, n + 1, &mut rand);
+        let count = vars.pop().unwrap();
+        let value = rand(5) as i64;
+        // bounds of the count only
+        check_gac(
+            &mut solver,
+            Box::new(CountConstraint::new(vars, value, count)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_at_most_at_least_sound() {
+    let mut rand = lcg(19);
+    for i in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let value = rand(5) as i64;
+        let bound = rand(n as u64 + 1) as i64;
+        // nothing is pruned before assignments
+        if i % 2 == 0 {
+            check_gac(&mut solver, Box::new(AtMostConstraint::new(vars, value, bound)), false);
+        } else {
+            check_gac(&mut solver, Box::new(AtLeastConstraint::new(vars, value, bound)), false);
+        }
+    }
+}
+
+#[test]
+fn test_nvalue_sound() {
+    let mut rand = lcg(31);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let mut vars = random_vars(&mut solver, n + 1, &mut rand);
+        let nvalue = vars.pop().unwrap();
+        // bounds of nvalue only
+        check_gac(&mut solver, Box::new(NValueConstraint::new(vars, nvalue)), false);
+    }
+}
+
+#[test]
+fn test_minimum_maximum_sound() {
+    let mut rand = lcg(47);
+    for i in 0..200 {
+        let n = 1 + rand(3) as usize;
+        let mut solver = new_solver();
+        let mut xs = random_vars(&mut solver, n + 1, &mut rand);
+        let result = xs.pop().unwrap();
+        // bounds consistency only
+        if i % 2 == 0 {
+            check_gac(&mut solver, Box::new(MinimumConstraint::new(result, xs)), false);
+        } else {
+            check_gac(&mut solver, Box::new(MaximumConstraint::new(result, xs)), false);
+        }
+    }
+}
+
+#[test]
+fn test_times_sound() {
+    let mut rand = lcg(53);
+    for _ in 0..300 {
+        let mut solver = new_solver();
+        // signs matter here, so domains are random subsets of {-3, ..., 3}
+        let vars: Vec<_> = (0..3)
+            .map(|i| {
+                let var = solver.new_variable(-3, 3, format!("x_{}", i));
+                let keep = rand(7) as i64 - 3;
+                for val in -3..=3 {
+                    if val != keep && rand(2) == 0 {
+                        var.borrow_mut().remove(val);
+                    }
+                }
+                var
+            })
+            .collect();
+        check_gac(
+            &mut solver,
+            Box::new(TimesConstraint::new(
+                vars[0].clone(),
+                vars[1].clone(),
+                vars[2].clone(),
+            )),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_lex_sound() {
+    let mut rand = lcg(59);
+    for i in 0..200 {
+        let n = 1 + rand(3) as usize;
+        let mut solver = new_solver();
+        let mut x = random_vars(&mut solver, 2 * n, &mut rand);
+        let y = x.split_off(n);
+        if i % 2 == 0 {
+            check_gac(&mut solver, Box::new(LexLessConstraint::new(x, y)), false);
+        } else {
+            check_gac(&mut solver, Box::new(LexLessEqConstraint::new(x, y)), false);
+        }
+    }
+}
+
+#[test]
+fn test_inverse_sound() {
+    let mut rand = lcg(61);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let mut x = random_vars(&mut solver, 2 * n, &mut rand);
+        let y = x.split_off(n);
+        check_gac(&mut solver, Box::new(InverseConstraint::new(x, y)), false);
+    }
+}
+
+#[test]
+fn test_bounded_gcc_gac() {
+    let mut rand = lcg(67);
+    for _ in 0..300 {
+        let n = 1 + rand(5) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let mut card = HashMap::new();
+        for val in 0..5 {
+            // some values are left out of the map
+            if rand(4) != 0 {
+                let lo = rand(2) as usize;
+                card.insert(val, (lo, lo + rand(3) as usize));
+            }
+        }
+        check_gac(&mut solver, Box::new(BoundedGccConstraint::new(vars, card)), true);
+    }
+}
+
+#[test]
+fn test_among_gac() {
+    let mut rand = lcg(71);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let mut vars = random_vars(&mut solver, n + 1, &mut rand);
+        let count = vars.pop().unwrap();
+        let values: HashSet<i64> = (0..5).filter(|_| rand(2) == 0).collect();
+        check_gac(&mut solver, Box::new(AmongConstraint::new(vars, values, count)), true);
+    }
+}
+
+#[test]
+fn test_xor_gac() {
+    let mut rand = lcg(73);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let odd = rand(2) == 0;
+        check_gac(&mut solver, Box::new(XorConstraint::new(vars, odd)), true);
+    }
+}
+
+#[test]
+fn test_knapsack_sound() {
+    let mut rand = lcg(79);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let mut vars = random_vars(&mut solver, n + 1, &mut rand);
+        let profit = vars.pop().unwrap();
+        let weights: Vec<i64> = (0..n).map(|_| rand(4) as i64).collect();
+        let profits: Vec<i64> = (0..n).map(|_| rand(3) as i64).collect();
+        let capacity = rand(6) as i64;
+        check_gac(
+            &mut solver,
+            Box::new(KnapsackConstraint::new(vars, weights, profits, capacity, profit)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_diffn_sound() {
+    let mut rand = lcg(83);
+    for _ in 0..200 {
+        let n = 2 + rand(2) as usize;
+        let mut solver = new_solver();
+        let x = random_vars(&mut solver, n, &mut rand);
+        let y = random_vars(&mut solver, n, &mut rand);
+        let widths = (0..n).map(|_| rand(3) as i64).collect();
+        let heights = (0..n).map(|_| rand(3) as i64).collect();
+        // pairwise bounds reasoning only
+        check_gac(
+            &mut solver,
+            Box::new(DiffnConstraint::new(x, y, widths, heights)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_value_precedence_gac() {
+    let mut rand = lcg(89);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        let s = rand(4) as i64;
+        let t = (s + 1 + rand(3) as i64) % 4;
+        check_gac(
+            &mut solver,
+            Box::new(ValuePrecedenceConstraint::new(vars, s, t)),
+            true,
+        );
+    }
+}
+
+#[test]
+fn test_abs_sound() {
+    let mut rand = lcg(97);
+    for _ in 0..200 {
+        let mut solver = new_solver();
+        // x also takes negative values, domains are random subsets of {-4, ..., 4}
+        let vars: Vec<_> = (0..2)
+            .map(|i| {
+                let var = solver.new_variable(-4, 4, format!("x_{}", i));
+                let keep = rand(9) as i64 - 4;
+                for val in -4..=4 {
+                    if val != keep && rand(2) == 0 {
+                        var.borrow_mut().remove(val);
+                    }
+                }
+                var
+            })
+            .collect();
+        // bounds consistency only, unless the domain consistent version is used
+        let ac = rand(2) == 0;
+        check_gac(
+            &mut solver,
+            Box::new(AbsConstraint::with_ac(vars[0].clone(), vars[1].clone(), ac)),
+            ac,
+        );
+    }
+}
+
+#[test]
+fn test_gcc_gac() {
+    let mut rand = lcg(101);
+    for _ in 0..200 {
+        let n = 1 + rand(4) as usize;
+        let mut solver = new_solver();
+        let vars = random_vars(&mut solver, n, &mut rand);
+        // some values get no count, so they are forbidden
+        let mut card = HashMap::new();
+        for val in 0..5 {
+            if rand(4) != 0 {
+                card.insert(val, rand(3) as usize);
+            }
+        }
+        check_gac(
+            &mut solver,
+            Box::new(GlobalCardinalityConstraint::new(vars, card)),
+            true,
+        );
+    }
+}
+
+#[test]
+fn test_var_bin_packing_sound() {
+    let mut rand = lcg(103);
+    for _ in 0..100 {
+        let n = 1 + rand(2) as usize;
+        let mut solver = new_solver();
+        let assignment = random_vars(&mut solver, n, &mut rand);
+        let weight = random_vars(&mut solver, n, &mut rand);
+        let load = random_vars(&mut solver, 2, &mut rand);
+        // bounds reasoning only
+        check_gac(
+            &mut solver,
+            Box::new(VarBinPackingConstraint::new(assignment, load, weight)),
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_sum_abs_deviation_sound() {
+    let mut rand = lcg(107);
+    for _ in 0..200 {
+        let n = 1 + rand(3) as usize;
+        let mut solver = new_solver();
+        let mut vars = random_vars(&mut solver, n + 1, &mut rand);
+        let cost = vars.pop().unwrap();
+        let target = rand(5) as i64;
+        // bounds reasoning, so only soundness is checked
+        check_gac(
+            &mut solver,
+            Box::new(SumAbsDeviationConstraint::new(vars, target, cost)),
+            false,
+        );
+    }
+}
@@ -1,10 +1,56 @@
-use ezcp::alldifferent::AllDifferentACPropagator;
-use ezcp::propagator::Propagator;
-use ezcp::solver::SolverState;
+use ezcp::alldifferent::{
+    AllDifferentACPropagator, AllDifferentConstraint, AllDifferentIfPropagator,
+};
+use ezcp::arithmetic::{
+    AbsACPropagator, AbsPropagator, MinMaxPropagator, SimpleArithmeticPropagator,
+    SumAbsDeviationPropagator, TimesPropagator,
+};
+use ezcp::binpacking::VarBinPackingPropagator;
+use ezcp::channel::{BoolChannelConstraint, BoolChannelPropagator, InversePropagator};
+use ezcp::config::Config;
+use ezcp::constraint::Constraint;
+use ezcp::count::{
+    AmongPropagator, AtLeastConstraint, AtLeastPropagator, AtMostConstraint, AtMostPropagator,
+    CountPropagator, NValuePropagator,
+};
+use ezcp::events::{event_index, Event};
+use ezcp::gcc::{BoundedGccACPropagator, BoundedGccConstraint, GlobalCardinalityACPropagator};
+use ezcp::graph::CircuitPropagator;
+use ezcp::knapsack::KnapsackPropagator;
+use ezcp::lex::{LexPropagator, ValuePrecedencePropagator};
+use ezcp::linear::{LinearInequalityConstraint, LinearInequalityPropagator, LinearLeVarConstraint};
+use ezcp::logic::{BitPropagator, OrPropagator, XorPropagator};
+use ezcp::propagator::{Propagator, Reason};
+use ezcp::regular::{RegularConstraint, RegularPropagator};
+use ezcp::scheduling::{
+    CumulativeTimeTablePropagator, DiffnConstraint, DiffnPropagator, DisjunctivePropagator,
+    NoOverlapPair, NoOverlapPairPropagator,
+};
+use ezcp::solver::{Solver, SolverState};
+use ezcp::table::{
+    NegativeTableConstraint, NegativeTablePropagator, TableConstraint, TablePropagator,
+};
+use ezcp::value_selector::MinValueSelector;
 use ezcp::variable::Variable;
+use ezcp::variable_selector::{FirstFailVariableSelector, LexVariableSelector};
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+fn new_var(
+    solver_state: &Rc<RefCell<SolverState>>,
+    lb: i64,
+    ub: i64,
+    name: &str,
+) -> Rc<RefCell<Variable>> {
+    Rc::new(RefCell::new(Variable::new(
+        solver_state.clone(),
+        lb,
+        ub,
+        name.to_string(),
+    )))
+}
+
 fn assert_domain(mut it: impl Iterator<Item = i64>, expected: Vec<i64>) {
     let mut it1 = expected.iter().cloned();
     loop {
@@ -66,3 +112,1139 @@ fn test_alldifferent() {
     assert_domain(z.borrow().iter(), vec![2]);
 }
 
+#[test]
+fn test_alldifferent_incremental_matching() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..8)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                7,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let mut p = AllDifferentACPropagator::new(vars.clone(), 0);
+    p.propagate();
+    assert_eq!(p.augmentations(), 8);
+    // only the variable losing its value has to be matched again
+    vars[0].borrow_mut().assign(7);
+    p.propagate();
+    assert_eq!(p.augmentations(), 9);
+    assert_domain(vars[0].borrow().iter(), vec![7]);
+    for v in &vars[1..] {
+        assert_domain(v.borrow().iter(), (0..7).collect());
+    }
+}
+
+#[test]
+fn test_linear_explanation() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state.clone(),
+        3,
+        10,
+        "x".to_string(),
+    )));
+    let y = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state,
+        0,
+        10,
+        "y".to_string(),
+    )));
+    let mut p = LinearInequalityPropagator::new(vec![x.clone(), y.clone()], vec![1, 1], 5, 0);
+    p.propagate();
+    assert_domain(y.borrow().iter(), vec![0, 1, 2]);
+    for val in 3..11 {
+        let explanation = p.explain(y.clone(), val);
+        assert_eq!(explanation.len(), 1);
+        let (var, bound, reason) = &explanation[0];
+        assert!(Rc::ptr_eq(var, &x));
+        assert_eq!(*reason, Reason::LowerBound);
+        assert!(x.borrow().get_lb() >= *bound);
+        assert!(bound + val > 5);
+    }
+}
+
+#[test]
+fn test_arithmetic_explanation() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state.clone(),
+        0,
+        5,
+        "x".to_string(),
+    )));
+    let y = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state,
+        3,
+        3,
+        "y".to_string(),
+    )));
+    let mut p = SimpleArithmeticPropagator::new(x.clone(), y.clone(), 5, true, 0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![2]);
+    for val in [0, 1, 3, 4, 5] {
+        let explanation = p.explain(x.clone(), val);
+        assert_eq!(explanation.len(), 1);
+        let (var, support, reason) = &explanation[0];
+        assert!(Rc::ptr_eq(var, &y));
+        assert_eq!(*reason, Reason::Removed);
+        assert_eq!(val + support, 5);
+        assert!(!y.borrow().possible(*support));
+    }
+}
+
+#[test]
+fn test_linear_le_var() {
+    let mut solver = Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(2, 5, "x".to_string());
+    let y = solver.new_variable(1, 4, "y".to_string());
+    let z = solver.new_variable(0, 20, "z".to_string());
+    solver.add_constraint(Box::new(LinearLeVarConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![2, 1],
+        z.clone(),
+    )));
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    // z is pushed up to 2 * 2 + 1 once x and y take their smallest values
+    assert_eq!(z.borrow().value(), 5);
+}
+
+#[test]
+fn test_bit() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state.clone(),
+        0,
+        15,
+        "x".to_string(),
+    )));
+    let b = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state.clone(),
+        0,
+        1,
+        "b".to_string(),
+    )));
+    let mut p = BitPropagator::new(x.clone(), 2, b.clone(), 0);
+    b.borrow_mut().assign(1);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![4, 5, 6, 7, 12, 13, 14, 15]);
+
+    let y = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state.clone(),
+        0,
+        15,
+        "y".to_string(),
+    )));
+    let c = Rc::new(RefCell::new(Variable::new(
+        fake_solver_state,
+        0,
+        1,
+        "c".to_string(),
+    )));
+    y.borrow_mut().tighten(8, 13);
+    for val in [8, 9, 12, 13] {
+        y.borrow_mut().remove(val);
+    }
+    let mut p = BitPropagator::new(y.clone(), 1, c.clone(), 1);
+    p.propagate();
+    // only 10 and 11 are left, both have bit 1 set
+    assert_eq!(c.borrow().try_value(), Some(1));
+}
+
+#[test]
+fn test_bool_channel() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = new_var(&fake_solver_state, -5, 5, "x");
+    let b: Vec<_> = (0..4)
+        .map(|j| new_var(&fake_solver_state, 0, 1, &format!("b_{}", j)))
+        .collect();
+    let mut p = BoolChannelPropagator::new(x.clone(), b.clone(), 0);
+    b[1].borrow_mut().assign(0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![0, 2, 3]);
+    assert!(!b[0].borrow().is_assigned());
+
+    x.borrow_mut().remove(0);
+    x.borrow_mut().remove(3);
+    p.propagate();
+    let values: Vec<_> = b.iter().map(|v| v.borrow().try_value()).collect();
+    assert_eq!(values, vec![Some(0), Some(0), Some(1), Some(0)]);
+}
+
+#[test]
+fn test_inverse() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x: Vec<_> = (0..3)
+        .map(|i| new_var(&fake_solver_state, 0, 5, &format!("x_{}", i)))
+        .collect();
+    let y: Vec<_> = (0..3)
+        .map(|j| new_var(&fake_solver_state, 0, 2, &format!("y_{}", j)))
+        .collect();
+    let mut p = InversePropagator::new(x.clone(), y.clone(), 0);
+    x[0].borrow_mut().remove(1);
+    y[2].borrow_mut().remove(1);
+    p.propagate();
+    assert_domain(x[0].borrow().iter(), vec![0, 2]);
+    assert_domain(x[1].borrow().iter(), vec![0, 1]);
+    assert_domain(y[1].borrow().iter(), vec![1, 2]);
+
+    x[1].borrow_mut().assign(0);
+    p.propagate();
+    assert_eq!(y[0].borrow().try_value(), Some(1));
+    // x[0] can not take 0 anymore, so only 2 is left
+    p.propagate();
+    assert_eq!(x[0].borrow().try_value(), Some(2));
+    assert_eq!(y[2].borrow().try_value(), Some(0));
+}
+
+#[test]
+fn test_bool_channel_search() {
+    let mut solver = Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    // booleans are created first, so search branches on them
+    let b: Vec<Vec<_>> = (0..3)
+        .map(|i| {
+            (0..3)
+                .map(|j| solver.new_variable(0, 1, format!("b_{}_{}", i, j)))
+                .collect()
+        })
+        .collect();
+    let x: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 2, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(BoolChannelConstraint::new(x.clone(), b)));
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(x.clone())));
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    let values: Vec<_> = x.iter().map(|v| v.borrow().value()).collect();
+    assert_eq!(values, vec![2, 1, 0]);
+}
+
+#[test]
+fn test_bool_channel_satisfied_out_of_range() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let b: Vec<_> = (0..2)
+        .map(|j| new_var(&fake_solver_state, 0, 0, &format!("b_{}", j)))
+        .collect();
+    // x is outside 0..b.len(), so no boolean can encode it
+    for value in [-1, 2] {
+        let x = new_var(&fake_solver_state, value, value, "x");
+        let c = BoolChannelConstraint::new(vec![x], vec![b.clone()]);
+        assert!(!c.satisfied());
+    }
+    let x = new_var(&fake_solver_state, 1, 1, "x");
+    let b1 = vec![
+        new_var(&fake_solver_state, 0, 0, "b_0"),
+        new_var(&fake_solver_state, 1, 1, "b_1"),
+    ];
+    assert!(BoolChannelConstraint::new(vec![x], vec![b1]).satisfied());
+}
+
+#[test]
+fn test_alldifferent_against_decomposition() {
+    let restrict = |var: &Rc<RefCell<Variable>>, dom: &Vec<i64>| {
+        for val in 0..5 {
+            if !dom.contains(&val) {
+                var.borrow_mut().remove(val);
+            }
+        }
+    };
+    // does some solution of the decomposition have vars[i] = val
+    let supported = |domains: &Vec<Vec<i64>>, i: usize, val: i64| {
+        let mut solver = Solver::new(
+            Box::new(FirstFailVariableSelector {}),
+            Box::new(MinValueSelector {}),
+        );
+        let mut vars = Vec::new();
+        for (j, dom) in domains.iter().enumerate() {
+            let dom = if j == i { vec![val] } else { dom.clone() };
+            let var = solver.new_variable(0, 4, format!("x_{}", j));
+            restrict(&var, &dom);
+            vars.push(var);
+        }
+        let violations = AllDifferentConstraint::new(vars).decompose_with_violations(&mut solver);
+        let n = violations.len();
+        solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+            violations,
+            vec![1; n],
+            0,
+        )));
+        solver.solve()
+    };
+
+    let mut seed: u64 = 17;
+    let mut rand = |m: u64| {
+        seed = (seed * 5 + 11) % (1 << 31);
+        (seed >> 8) % m
+    };
+    for _ in 0..40 {
+        let n = 2 + rand(3) as usize;
+        let domains: Vec<Vec<i64>> = (0..n)
+            .map(|_| {
+                let dom: Vec<i64> = (0..5).filter(|_| rand(2) == 0).collect();
+                if dom.is_empty() {
+                    vec![rand(5) as i64]
+                } else {
+                    dom
+                }
+            })
+            .collect();
+        let expected: Vec<Vec<i64>> = (0..n)
+            .map(|i| {
+                domains[i]
+                    .iter()
+                    .cloned()
+                    .filter(|val| supported(&domains, i, *val))
+                    .collect()
+            })
+            .collect();
+        if expected.iter().any(|dom| dom.is_empty()) {
+            // no solution, the propagator fails instead of pruning
+            continue;
+        }
+        let state = Rc::new(RefCell::new(SolverState::new()));
+        let vars: Vec<_> = domains
+            .iter()
+            .enumerate()
+            .map(|(i, dom)| {
+                let var = Rc::new(RefCell::new(Variable::new(
+                    state.clone(),
+                    0,
+                    4,
+                    format!("x_{}", i),
+                )));
+                restrict(&var, dom);
+                var
+            })
+            .collect();
+        let mut p = AllDifferentACPropagator::new(vars.clone(), 0);
+        p.propagate();
+        for (var, dom) in vars.iter().zip(expected) {
+            assert_domain(var.borrow().iter(), dom);
+        }
+    }
+}
+
+#[test]
+fn test_estimated_cost() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..5)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                9,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let binary = SimpleArithmeticPropagator::new(vars[0].clone(), vars[1].clone(), 0, false, 0);
+    let linear = LinearInequalityPropagator::new(vars.clone(), vec![1; 5], 10, 1);
+    let alldiff = AllDifferentACPropagator::new(vars.clone(), 2);
+    assert!(binary.estimated_cost() < linear.estimated_cost());
+    assert!(linear.estimated_cost() < alldiff.estimated_cost());
+    assert_eq!(alldiff.estimated_cost(), 50);
+}
+
+#[test]
+fn test_gcc() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..3)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                2,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let card: HashMap<i64, i32> = [(0, 1), (1, 2), (2, 2)].into_iter().collect();
+    let mut p = GlobalCardinalityACPropagator::new(vars.clone(), card, 0);
+    // every value has support
+    p.propagate();
+    for v in &vars {
+        assert_domain(v.borrow().iter(), vec![0, 1, 2]);
+    }
+    // value 0 is used up by x_0
+    vars[0].borrow_mut().assign(0);
+    p.propagate();
+    assert_domain(vars[1].borrow().iter(), vec![1, 2]);
+    assert_domain(vars[2].borrow().iter(), vec![1, 2]);
+}
+
+#[test]
+fn test_bounded_gcc() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..4)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                3,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    // 3 is not mentioned, 0 must be taken twice
+    let card: HashMap<i64, (i32, i32)> = [(0, (2, 2)), (1, (0, 2)), (2, (1, 1))]
+        .into_iter()
+        .collect();
+    let mut p = BoundedGccACPropagator::new(vars.clone(), card, fake_solver_state.clone(), 0);
+    vars[0].borrow_mut().remove(0);
+    vars[1].borrow_mut().remove(0);
+    p.propagate();
+    // x_2 and x_3 are the only candidates for 0
+    assert_domain(vars[0].borrow().iter(), vec![1, 2]);
+    assert_domain(vars[1].borrow().iter(), vec![1, 2]);
+    assert_domain(vars[2].borrow().iter(), vec![0]);
+    assert_domain(vars[3].borrow().iter(), vec![0]);
+    // a value which has to be taken fails without variables
+    let mut solver = Solver::with_config(Config::default());
+    let card: HashMap<i64, (usize, usize)> = [(0, (1, 2))].into_iter().collect();
+    solver.add_constraint(Box::new(BoundedGccConstraint::new(Vec::new(), card)));
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_alldifferent_if() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 1, &format!("x_{}", i)))
+        .collect();
+    let present: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 1, &format!("p_{}", i)))
+        .collect();
+    let mut p = AllDifferentIfPropagator::new(vars.clone(), present.clone(), 0);
+    // nothing is known to be present, 4 variables over 2 values are fine
+    p.propagate();
+    for v in &vars {
+        assert_domain(v.borrow().iter(), vec![0, 1]);
+    }
+    present[0].borrow_mut().assign(1);
+    present[1].borrow_mut().assign(1);
+    vars[0].borrow_mut().assign(0);
+    p.propagate();
+    assert_domain(vars[1].borrow().iter(), vec![1]);
+    // both values are taken, so the rest must be absent
+    assert_eq!(present[2].borrow().try_value(), Some(0));
+    assert_eq!(present[3].borrow().try_value(), Some(0));
+    assert_domain(vars[2].borrow().iter(), vec![0, 1]);
+}
+
+#[test]
+fn test_table() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..3)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                3,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let tuples = vec![vec![0, 1, 2], vec![1, 1, 3], vec![2, 0, 0], vec![3, 2, 1]];
+    let mut p = TablePropagator::new(vars.clone(), tuples, 0);
+    p.propagate();
+    assert_domain(vars[0].borrow().iter(), vec![0, 1, 2, 3]);
+    assert_domain(vars[1].borrow().iter(), vec![0, 1, 2]);
+    assert_domain(vars[2].borrow().iter(), vec![0, 1, 2, 3]);
+    // only (1, 1, 3) and (3, 2, 1) are left
+    vars[1].borrow_mut().remove(0);
+    vars[2].borrow_mut().remove(2);
+    p.propagate();
+    assert_domain(vars[0].borrow().iter(), vec![1, 3]);
+    assert_domain(vars[1].borrow().iter(), vec![1, 2]);
+    assert_domain(vars[2].borrow().iter(), vec![1, 3]);
+}
+
+#[test]
+fn test_negative_table() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..3)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                2,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let tuples = vec![vec![0, 1, 2], vec![0, 1, 0], vec![1, 0, 5]];
+    let c = NegativeTableConstraint::new(vars.clone(), tuples.clone());
+    let mut p = NegativeTablePropagator::new(vars.clone(), tuples, fake_solver_state.clone(), 0);
+    p.propagate();
+    assert_domain(vars[2].borrow().iter(), vec![0, 1, 2]);
+    vars[0].borrow_mut().assign(0);
+    p.propagate();
+    assert_domain(vars[2].borrow().iter(), vec![0, 1, 2]);
+    vars[1].borrow_mut().assign(1);
+    p.propagate();
+    assert_domain(vars[2].borrow().iter(), vec![1]);
+    assert!(c.satisfied());
+    // the empty tuple is the only assignment of an empty scope
+    let mut solver = Solver::with_config(Config::default());
+    solver.add_constraint(Box::new(NegativeTableConstraint::new(
+        Vec::new(),
+        vec![Vec::new()],
+    )));
+    assert!(!solver.solve());
+    // the same propagator comes from negating a table which allows it
+    let c = TableConstraint::new(Vec::new(), vec![Vec::new()]);
+    let mut solver = Solver::with_config(Config::default());
+    c.negation()
+        .unwrap()
+        .borrow()
+        .create_propagators(&mut solver);
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_regular() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..4)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                2,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    // words over {0, 1} without two consecutive ones, 2 has no transition
+    let transitions = vec![vec![0, 1], vec![0, 2], vec![2, 2]];
+    let mut p = RegularPropagator::new(
+        vars.clone(),
+        transitions,
+        0,
+        vec![0, 1],
+        fake_solver_state.clone(),
+        0,
+    );
+    p.propagate();
+    for v in &vars {
+        assert_domain(v.borrow().iter(), vec![0, 1]);
+    }
+    vars[1].borrow_mut().assign(1);
+    p.propagate();
+    assert_domain(vars[0].borrow().iter(), vec![0]);
+    assert_domain(vars[2].borrow().iter(), vec![0]);
+    assert_domain(vars[3].borrow().iter(), vec![0, 1]);
+    // the empty word is accepted only if the start state is accepting
+    for (accepting, count) in [(vec![1], 0), (vec![0], 1)] {
+        let mut solver = Solver::with_config(Config::default());
+        solver.add_constraint(Box::new(RegularConstraint::new(
+            Vec::new(),
+            vec![vec![0, 1], vec![0, 2], vec![2, 2]],
+            0,
+            accepting,
+        )));
+        assert_eq!(solver.count_solutions(), count);
+    }
+}
+
+#[test]
+fn test_cumulative() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // a surely runs in [2, 4), b does not fit next to it, c has zero demand
+    let a = new_var(&fake_solver_state, 1, 2, "a");
+    let b = new_var(&fake_solver_state, 1, 10, "b");
+    let c = new_var(&fake_solver_state, 0, 10, "c");
+    let mut p = CumulativeTimeTablePropagator::new(
+        vec![a.clone(), b.clone(), c.clone()],
+        vec![3, 2, 5],
+        vec![2, 2, 0],
+        3,
+        0,
+    );
+    p.propagate();
+    assert_domain(a.borrow().iter(), vec![1, 2]);
+    assert_domain(b.borrow().iter(), (4..=10).collect());
+    assert_domain(c.borrow().iter(), (0..=10).collect());
+    // b runs in [4, 6), so a has to end by 4
+    b.borrow_mut().assign(4);
+    p.propagate();
+    assert_domain(a.borrow().iter(), vec![1]);
+}
+
+#[test]
+fn test_disjunctive() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // a and b fill [0, 4), so c has to run after both, no pair of tasks alone shows it
+    let a = new_var(&fake_solver_state, 0, 2, "a");
+    let b = new_var(&fake_solver_state, 0, 2, "b");
+    let c = new_var(&fake_solver_state, 1, 10, "c");
+    let mut p = DisjunctivePropagator::new(vec![a.clone(), b.clone(), c.clone()], vec![2, 2, 3], 0);
+    p.propagate();
+    assert_domain(a.borrow().iter(), vec![0, 1, 2]);
+    assert_domain(b.borrow().iter(), vec![0, 1, 2]);
+    assert_domain(c.borrow().iter(), (4..=10).collect());
+}
+
+#[test]
+fn test_circuit() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let succ: Vec<_> = (0..4)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                5,
+                format!("succ_{}", i),
+            )))
+        })
+        .collect();
+    let mut p = CircuitPropagator::new(succ.clone(), 0);
+    p.propagate();
+    assert_domain(succ[0].borrow().iter(), vec![1, 2, 3]);
+    assert_domain(succ[2].borrow().iter(), vec![0, 1, 3]);
+    // chain 0 -> 1 -> 2 can not be closed by 2 -> 0
+    succ[0].borrow_mut().assign(1);
+    succ[1].borrow_mut().assign(2);
+    p.propagate();
+    assert_domain(succ[2].borrow().iter(), vec![1, 3]);
+}
+
+#[test]
+fn test_count() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 3, &format!("x_{}", i)))
+        .collect();
+    let count = new_var(&fake_solver_state, 0, 10, "count");
+    vars[0].borrow_mut().assign(2);
+    vars[1].borrow_mut().remove(2);
+    let mut p = CountPropagator::new(vars.clone(), 2, count.clone(), 0);
+    p.propagate();
+    assert_domain(count.borrow().iter(), vec![1, 2, 3]);
+    // the value is taken by x_0 only
+    count.borrow_mut().set_ub(1);
+    p.propagate();
+    for v in &vars[1..] {
+        assert!(!v.borrow().possible(2));
+    }
+    let count = new_var(&fake_solver_state, 3, 3, "count");
+    let vars: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 3, &format!("y_{}", i)))
+        .collect();
+    vars[0].borrow_mut().remove(1);
+    let mut p = CountPropagator::new(vars.clone(), 1, count, 0);
+    p.propagate();
+    for v in &vars[1..] {
+        assert_domain(v.borrow().iter(), vec![1]);
+    }
+}
+
+#[test]
+fn test_among() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let values: HashSet<i64> = [1, 3].into_iter().collect();
+    // x_0 is inside the set, x_1 is outside, x_2 and x_3 can go either way
+    let vars = vec![
+        new_var(&fake_solver_state, 1, 1, "x_0"),
+        new_var(&fake_solver_state, 4, 5, "x_1"),
+        new_var(&fake_solver_state, 0, 3, "x_2"),
+        new_var(&fake_solver_state, 2, 3, "x_3"),
+    ];
+    let count = new_var(&fake_solver_state, 0, 10, "count");
+    let mut p = AmongPropagator::new(vars.clone(), values.clone(), count.clone(), 0);
+    p.propagate();
+    assert_domain(count.borrow().iter(), vec![1, 2, 3]);
+    count.borrow_mut().set_lb(3);
+    p.propagate();
+    assert_domain(vars[2].borrow().iter(), vec![1, 3]);
+    assert_domain(vars[3].borrow().iter(), vec![3]);
+
+    let vars: Vec<_> = (0..3)
+        .map(|i| new_var(&fake_solver_state, 0, 3, &format!("y_{}", i)))
+        .collect();
+    vars[0].borrow_mut().assign(3);
+    let count = new_var(&fake_solver_state, 0, 1, "count");
+    let mut p = AmongPropagator::new(vars.clone(), values, count, 0);
+    p.propagate();
+    for v in &vars[1..] {
+        assert_domain(v.borrow().iter(), vec![0, 2]);
+    }
+}
+
+#[test]
+fn test_at_most_at_least() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let new_vars = || -> Vec<_> {
+        (0..4)
+            .map(|i| {
+                Rc::new(RefCell::new(Variable::new(
+                    fake_solver_state.clone(),
+                    0,
+                    3,
+                    format!("x_{}", i),
+                )))
+            })
+            .collect()
+    };
+    let vars = new_vars();
+    let mut p = AtMostPropagator::new(vars.clone(), 1, 2, fake_solver_state.clone(), 0);
+    vars[0].borrow_mut().assign(1);
+    p.propagate();
+    assert!(vars[1].borrow().possible(1));
+    vars[1].borrow_mut().assign(1);
+    p.propagate();
+    assert!(!vars[2].borrow().possible(1));
+    assert!(!vars[3].borrow().possible(1));
+
+    let vars = new_vars();
+    let mut p = AtLeastPropagator::new(vars.clone(), 2, 3, fake_solver_state.clone(), 0);
+    vars[0].borrow_mut().assign(2);
+    p.propagate();
+    assert!(!vars[1].borrow().is_assigned());
+    vars[1].borrow_mut().assign(0);
+    p.propagate();
+    assert_domain(vars[2].borrow().iter(), vec![2]);
+    assert_domain(vars[3].borrow().iter(), vec![2]);
+    // counts which can not be met fail without variables
+    let mut solver = Solver::with_config(Config::default());
+    solver.add_constraint(Box::new(AtLeastConstraint::new(Vec::new(), 0, 1)));
+    assert!(!solver.solve());
+    let mut solver = Solver::with_config(Config::default());
+    solver.add_constraint(Box::new(AtMostConstraint::new(Vec::new(), 0, -1)));
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_nvalue() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // {0, 1}, {1, 2}, {3}, {5, 7}, {7}, so at least 3 values and at most one per variable
+    let vars = vec![
+        new_var(&fake_solver_state, 0, 1, "a"),
+        new_var(&fake_solver_state, 1, 2, "b"),
+        new_var(&fake_solver_state, 3, 3, "c"),
+        new_var(&fake_solver_state, 5, 7, "d"),
+        new_var(&fake_solver_state, 7, 7, "e"),
+    ];
+    vars[3].borrow_mut().remove(6);
+    let nvalue = new_var(&fake_solver_state, 0, 10, "nvalue");
+    let mut p = NValuePropagator::new(vars.clone(), nvalue.clone(), 0);
+    p.propagate();
+    assert_domain(nvalue.borrow().iter(), vec![3, 4, 5]);
+    vars[0].borrow_mut().assign(0);
+    vars[1].borrow_mut().assign(2);
+    p.propagate();
+    assert_domain(nvalue.borrow().iter(), vec![4, 5]);
+    // four variables, but only the 3 values of [0, 2] to take
+    let vars = vec![
+        new_var(&fake_solver_state, 0, 1, "a"),
+        new_var(&fake_solver_state, 1, 2, "b"),
+        new_var(&fake_solver_state, 1, 2, "c"),
+        new_var(&fake_solver_state, 0, 2, "d"),
+    ];
+    let nvalue = new_var(&fake_solver_state, 0, 10, "nvalue");
+    let mut p = NValuePropagator::new(vars.clone(), nvalue.clone(), 0);
+    p.propagate();
+    assert_domain(nvalue.borrow().iter(), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_minimum_maximum() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let xs = vec![
+        new_var(&fake_solver_state, 3, 9, "a"),
+        new_var(&fake_solver_state, 5, 7, "b"),
+        new_var(&fake_solver_state, 6, 12, "c"),
+    ];
+    let result = new_var(&fake_solver_state, 0, 20, "min");
+    let mut p = MinMaxPropagator::new(result.clone(), xs.clone(), false, 0);
+    p.propagate();
+    assert_domain(result.borrow().iter(), (3..=7).collect());
+    result.borrow_mut().set_lb(6);
+    p.propagate();
+    assert_domain(xs[0].borrow().iter(), (6..=9).collect());
+    assert_domain(xs[1].borrow().iter(), vec![6, 7]);
+    // only b can be as small as 6 now
+    xs[0].borrow_mut().set_lb(8);
+    xs[2].borrow_mut().set_lb(8);
+    p.propagate();
+    assert_domain(result.borrow().iter(), vec![6, 7]);
+    xs[1].borrow_mut().remove(7);
+    p.propagate();
+    assert_domain(result.borrow().iter(), vec![6]);
+
+    let xs = vec![
+        new_var(&fake_solver_state, 3, 9, "a"),
+        new_var(&fake_solver_state, 5, 7, "b"),
+    ];
+    let result = new_var(&fake_solver_state, 8, 20, "max");
+    let mut p = MinMaxPropagator::new(result.clone(), xs.clone(), true, 0);
+    p.propagate();
+    assert_domain(result.borrow().iter(), vec![8, 9]);
+    // a is the only one which reaches 8
+    assert_domain(xs[0].borrow().iter(), vec![8, 9]);
+    assert_domain(xs[1].borrow().iter(), (5..=7).collect());
+}
+
+#[test]
+fn test_times() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = new_var(&fake_solver_state, 2, 5, "x");
+    let y = new_var(&fake_solver_state, -3, 4, "y");
+    let z = new_var(&fake_solver_state, -100, 100, "z");
+    let mut p = TimesPropagator::new(x.clone(), y.clone(), z.clone(), 0);
+    p.propagate();
+    assert_domain(z.borrow().iter(), (-15..=20).collect());
+    // y = z / x is in [7 / 5, 9 / 2]
+    z.borrow_mut().tighten(7, 9);
+    p.propagate();
+    assert_domain(y.borrow().iter(), (2..=4).collect());
+    // x = z / y is in [7 / 4, 9 / 2], visible after the next run
+    p.propagate();
+    assert_domain(x.borrow().iter(), (2..=4).collect());
+    assert_domain(z.borrow().iter(), (7..=9).collect());
+
+    // nonzero product, so zero bounds are excluded from the factors
+    let x = new_var(&fake_solver_state, 0, 3, "x");
+    let y = new_var(&fake_solver_state, -2, 0, "y");
+    let z = new_var(&fake_solver_state, -6, -1, "z");
+    let mut p = TimesPropagator::new(x.clone(), y.clone(), z.clone(), 0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), (1..=3).collect());
+    assert_domain(y.borrow().iter(), vec![-2, -1]);
+    assert_domain(z.borrow().iter(), (-6..=-1).collect());
+}
+
+#[test]
+fn test_lex() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // x = (2, 0..3, 4) < y = (2, 0..3, 1)
+    let x = vec![
+        new_var(&fake_solver_state, 2, 2, "x_0"),
+        new_var(&fake_solver_state, 0, 3, "x_1"),
+        new_var(&fake_solver_state, 4, 4, "x_2"),
+    ];
+    let y = vec![
+        new_var(&fake_solver_state, 2, 2, "y_0"),
+        new_var(&fake_solver_state, 0, 3, "y_1"),
+        new_var(&fake_solver_state, 1, 1, "y_2"),
+    ];
+    let mut p = LexPropagator::new(x.clone(), y.clone(), true, 0);
+    p.propagate();
+    // the last position can not break the tie, so x_1 < y_1
+    assert_domain(x[1].borrow().iter(), vec![0, 1, 2]);
+    assert_domain(y[1].borrow().iter(), vec![1, 2, 3]);
+    y[1].borrow_mut().set_ub(1);
+    p.propagate();
+    assert_domain(x[1].borrow().iter(), vec![0]);
+
+    // equal tuples are allowed by the non-strict order
+    let x = vec![
+        new_var(&fake_solver_state, 1, 1, "x_0"),
+        new_var(&fake_solver_state, 0, 1, "x_1"),
+    ];
+    let y = vec![
+        new_var(&fake_solver_state, 1, 1, "y_0"),
+        new_var(&fake_solver_state, 0, 0, "y_1"),
+    ];
+    let mut p = LexPropagator::new(x.clone(), y.clone(), false, 0);
+    p.propagate();
+    assert_domain(x[1].borrow().iter(), vec![0]);
+}
+
+#[test]
+fn test_or_watched_literals() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let result = new_var(&fake_solver_state, 1, 1, "result");
+    let vars: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 1, &format!("x_{}", i)))
+        .collect();
+    let p = Rc::new(RefCell::new(OrPropagator::new(result, vars.clone(), 0)));
+    p.borrow_mut().propagate();
+    p.borrow().listen(p.clone());
+    let listened: Vec<bool> = vars
+        .iter()
+        .map(|v| v.borrow().listeners[event_index(&Event::UpperBound)].contains(0))
+        .collect();
+    assert_eq!(listened.iter().filter(|x| **x).count(), 2);
+    for v in &vars[..3] {
+        v.borrow_mut().assign(0);
+        p.borrow_mut().propagate();
+    }
+    assert_eq!(vars[3].borrow().try_value(), Some(1));
+}
+
+#[test]
+fn test_xor() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let vars: Vec<_> = (0..3)
+        .map(|i| {
+            Rc::new(RefCell::new(Variable::new(
+                fake_solver_state.clone(),
+                0,
+                1,
+                format!("x_{}", i),
+            )))
+        })
+        .collect();
+    let mut p = XorPropagator::new(vars.clone(), true, 0);
+    vars[0].borrow_mut().assign(1);
+    p.propagate();
+    assert!(!vars[1].borrow().is_assigned() && !vars[2].borrow().is_assigned());
+    vars[2].borrow_mut().assign(1);
+    p.propagate();
+    // 1 + x_1 + 1 is odd
+    assert_eq!(vars[1].borrow().try_value(), Some(1));
+}
+
+#[test]
+fn test_knapsack() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x: Vec<_> = (0..3)
+        .map(|i| new_var(&fake_solver_state, 0, 1, &format!("x_{}", i)))
+        .collect();
+    let profit = new_var(&fake_solver_state, 7, 20, "profit");
+    let mut p = KnapsackPropagator::new(
+        x.clone(),
+        vec![3, 3, 2],
+        vec![6, 5, 1],
+        5,
+        profit.clone(),
+        0,
+    );
+    p.propagate();
+    // the relaxation gives 6 + 5 * 2 / 3, without item 0 only 6 is reachable
+    let values: Vec<_> = x.iter().map(|v| v.borrow().try_value()).collect();
+    assert_eq!(values, vec![Some(1), Some(0), Some(1)]);
+    p.propagate();
+    assert_domain(profit.borrow().iter(), vec![7]);
+}
+
+#[test]
+fn test_no_overlap_pair() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // both orders are possible at first
+    let a = new_var(&fake_solver_state, 2, 4, "a");
+    let b = new_var(&fake_solver_state, 0, 8, "b");
+    let mut p = NoOverlapPairPropagator::new(a.clone(), 3, b.clone(), 3, 0);
+    p.propagate();
+    assert_domain(a.borrow().iter(), vec![2, 3, 4]);
+    assert_domain(b.borrow().iter(), (0..9).collect());
+    // once b has to start before a ends, b goes first
+    b.borrow_mut().set_ub(4);
+    p.propagate();
+    assert_domain(a.borrow().iter(), vec![3, 4]);
+    assert_domain(b.borrow().iter(), vec![0, 1]);
+    // a zero length task prunes nothing
+    let c = new_var(&fake_solver_state, 0, 2, "c");
+    let d = new_var(&fake_solver_state, 0, 2, "d");
+    let mut p = NoOverlapPairPropagator::new(c.clone(), 0, d.clone(), 3, 0);
+    p.propagate();
+    assert_domain(c.borrow().iter(), vec![0, 1, 2]);
+    assert_domain(d.borrow().iter(), vec![0, 1, 2]);
+    // a task of positive length can not avoid itself
+    let mut solver = Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 3, "x".to_string());
+    solver.add_constraint(Box::new(NoOverlapPair::new(x.clone(), 1, x.clone(), 2)));
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_diffn() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // 2x2 squares, the second one can not leave the columns of the first, so it goes above it
+    let xa = new_var(&fake_solver_state, 0, 0, "xa");
+    let ya = new_var(&fake_solver_state, 0, 0, "ya");
+    let xb = new_var(&fake_solver_state, 0, 1, "xb");
+    let yb = new_var(&fake_solver_state, 0, 3, "yb");
+    let mut p = DiffnPropagator::new(
+        vec![xa.clone(), xb.clone()],
+        vec![ya.clone(), yb.clone()],
+        vec![2, 2],
+        vec![2, 2],
+        0,
+    );
+    p.propagate();
+    assert_domain(xb.borrow().iter(), vec![0, 1]);
+    assert_domain(yb.borrow().iter(), vec![2, 3]);
+    // a rectangle of zero width overlaps nothing
+    let xc = new_var(&fake_solver_state, 0, 0, "xc");
+    let yc = new_var(&fake_solver_state, 0, 0, "yc");
+    let mut p = DiffnPropagator::new(
+        vec![xa.clone(), xc.clone()],
+        vec![ya.clone(), yc.clone()],
+        vec![2, 0],
+        vec![2, 2],
+        0,
+    );
+    p.propagate();
+    assert_domain(xc.borrow().iter(), vec![0]);
+    assert_domain(yc.borrow().iter(), vec![0]);
+}
+
+#[test]
+fn test_diffn_shared_coordinates() {
+    // a shared x can not separate the squares, so they are stacked vertically
+    let mut solver = Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let ya = solver.new_variable(0, 0, "ya".to_string());
+    let yb = solver.new_variable(0, 3, "yb".to_string());
+    solver.add_constraint(Box::new(DiffnConstraint::new(
+        vec![x.clone(), x.clone()],
+        vec![ya.clone(), yb.clone()],
+        vec![2, 2],
+        vec![2, 2],
+    )));
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    assert_eq!(yb.borrow().value(), 2);
+    // sharing both coordinates leaves no axis to separate along
+    let mut solver = Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    solver.add_constraint(Box::new(DiffnConstraint::new(
+        vec![x.clone(), x.clone()],
+        vec![y.clone(), y.clone()],
+        vec![2, 1],
+        vec![1, 2],
+    )));
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_value_precedence() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // 0 precedes 1, x_0 can not hold 0
+    let x: Vec<_> = (0..4)
+        .map(|i| new_var(&fake_solver_state, 0, 2, &format!("x_{}", i)))
+        .collect();
+    x[0].borrow_mut().remove(0);
+    let mut p = ValuePrecedencePropagator::new(x.clone(), 0, 1, 0);
+    p.propagate();
+    assert_domain(x[0].borrow().iter(), vec![2]);
+    assert_domain(x[1].borrow().iter(), vec![0, 2]);
+    assert_domain(x[2].borrow().iter(), vec![0, 1, 2]);
+    // x_2 is the first 1, so x_1 is the only place left for 0
+    x[2].borrow_mut().assign(1);
+    p.propagate();
+    assert_domain(x[1].borrow().iter(), vec![0]);
+    assert_domain(x[3].borrow().iter(), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_sum_abs_deviation() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // a small cost keeps both variables close to the target
+    let x = new_var(&fake_solver_state, 0, 10, "x");
+    let y = new_var(&fake_solver_state, 3, 5, "y");
+    let cost = new_var(&fake_solver_state, 0, 2, "cost");
+    let mut p = SumAbsDeviationPropagator::new(vec![x.clone(), y.clone()], 4, cost.clone(), 0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), (2..7).collect());
+    assert_domain(y.borrow().iter(), vec![3, 4, 5]);
+    assert_domain(cost.borrow().iter(), vec![0, 1, 2]);
+    // the cost is bounded by the deviations the variables can take
+    let x = new_var(&fake_solver_state, 0, 2, "x");
+    let cost = new_var(&fake_solver_state, 0, 10, "cost");
+    let mut p = SumAbsDeviationPropagator::new(vec![x.clone()], 4, cost.clone(), 0);
+    p.propagate();
+    assert_domain(cost.borrow().iter(), vec![2, 3, 4]);
+    // a large cost pushes the variable away from the target
+    let x = new_var(&fake_solver_state, 3, 6, "x");
+    let cost = new_var(&fake_solver_state, 2, 2, "cost");
+    let mut p = SumAbsDeviationPropagator::new(vec![x.clone()], 4, cost.clone(), 0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![6]);
+}
+
+#[test]
+fn test_abs() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // x spans zero
+    let x = new_var(&fake_solver_state, -3, 8, "x");
+    let y = new_var(&fake_solver_state, -2, 5, "y");
+    let mut p = AbsPropagator::new(x.clone(), y.clone(), 0);
+    p.propagate();
+    assert_domain(y.borrow().iter(), (0..=5).collect());
+    assert_domain(x.borrow().iter(), (-3..=5).collect());
+    // x can not reach -4, so it has to be positive
+    y.borrow_mut().set_lb(4);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![4, 5]);
+
+    // fully negative x
+    let x = new_var(&fake_solver_state, -7, -2, "x");
+    let y = new_var(&fake_solver_state, 0, 10, "y");
+    let mut p = AbsPropagator::new(x.clone(), y.clone(), 0);
+    p.propagate();
+    assert_domain(y.borrow().iter(), (2..=7).collect());
+    y.borrow_mut().set_ub(4);
+    p.propagate();
+    assert_domain(x.borrow().iter(), (-4..=-2).collect());
+}
+
+#[test]
+fn test_abs_ac() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = new_var(&fake_solver_state, -6, 6, "x");
+    let y = new_var(&fake_solver_state, 0, 5, "y");
+    for v in [0, 1, 3, 4] {
+        y.borrow_mut().remove(v);
+    }
+    let mut p = AbsACPropagator::new(x.clone(), y.clone(), 0);
+    p.propagate();
+    assert_domain(x.borrow().iter(), vec![-5, -2, 2, 5]);
+    // y loses 5 once neither 5 nor -5 is left in x
+    x.borrow_mut().remove(5);
+    x.borrow_mut().remove(-5);
+    p.propagate();
+    assert_domain(y.borrow().iter(), vec![2]);
+}
+
+#[test]
+fn test_var_bin_packing() {
+    let fake_solver_state = Rc::new(RefCell::new(SolverState::new()));
+    // item 0 is in bin 0, item 1 can go anywhere
+    let assignment = vec![
+        new_var(&fake_solver_state, 0, 0, "a_0"),
+        new_var(&fake_solver_state, 0, 2, "a_1"),
+    ];
+    let weight = vec![
+        new_var(&fake_solver_state, 2, 4, "w_0"),
+        new_var(&fake_solver_state, 3, 5, "w_1"),
+    ];
+    let load = vec![
+        new_var(&fake_solver_state, 0, 10, "l_0"),
+        new_var(&fake_solver_state, 0, 4, "l_1"),
+    ];
+    let mut p = VarBinPackingPropagator::new(assignment.clone(), load.clone(), weight.clone(), 0);
+    p.propagate();
+    // there are two bins only
+    assert_domain(assignment[1].borrow().iter(), vec![0, 1]);
+    assert_domain(load[0].borrow().iter(), (2..=9).collect());
+    assert_domain(load[1].borrow().iter(), (0..=4).collect());
+    // bin 1 has to hold 4, only item 1 can fill it
+    load[1].borrow_mut().assign(4);
+    p.propagate();
+    assert_domain(assignment[1].borrow().iter(), vec![1]);
+    p.propagate();
+    assert_domain(weight[1].borrow().iter(), vec![4]);
+    assert_domain(load[0].borrow().iter(), (2..=4).collect());
+}
@@ -1,13 +1,28 @@
-use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::alldifferent::{AllDifferentACPropagator, AllDifferentConstraint};
+use ezcp::arithmetic::SimpleArithmeticConstraint;
+use ezcp::config::{Config, RestartStrategy};
+use ezcp::domain::Domain;
+use ezcp::graph::CircuitConstraint;
 use ezcp::linear::LinearInequalityConstraint;
-use ezcp::objective_function::ObjectiveFunction;
-use ezcp::solver::Solver;
-use ezcp::value_selector::MinValueSelector;
+use ezcp::objective_function::{
+    AllDifferentSumBound, LexObjective, LinearObjective, ObjectiveBound, ObjectiveFunction,
+};
+use ezcp::propagator::{Propagator, PropagatorControlBlock};
+use ezcp::reify::{reify_eq, reify_le, reify_lt};
+use ezcp::solver::{binary_search_optimizer, SearchStats, Solver, SolverState};
+use ezcp::value_selector::{
+    CustomOrderValueSelector, MaxValueSelector, MedianValueSelector, MinValueSelector,
+    RandomValueSelector, ValueOrderValueSelector, ValueSelector,
+};
 use ezcp::variable::Variable;
-use ezcp::variable_selector::FirstFailVariableSelector;
+use ezcp::variable_selector::{
+    DomWDegVariableSelector, FirstFailRandomTieVariableSelector, FirstFailVariableSelector,
+    RandomVariableSelector, ValueOrderVariableSelector, VariableSelector,
+};
 use std::boxed::Box;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 struct SumObjective {
     vars: Vec<Rc<RefCell<Variable>>>,
@@ -55,3 +70,809 @@ fn test_optimization() {
     assert!(solver.solve());
     assert!(solver.get_objective() == 45);
 }
+
+fn solve_nqueens(config: Config, n: usize) -> Vec<i64> {
+    let mut solver = Solver::with_config(config);
+    let mut vars = Vec::with_capacity(n);
+    let mut diag1 = Vec::with_capacity(n);
+    let mut diag2 = Vec::with_capacity(n);
+    for i in 0..n {
+        vars.push(solver.new_variable(0, (n as i64) - 1, format!("pos_{}", i)));
+        diag1.push(solver.new_variable(i as i64, (n + i - 1) as i64, format!("+diag_{}", i)));
+        diag2.push(solver.new_variable(
+            -(i as i64),
+            (n as i64) - 1 - (i as i64),
+            format!("-diag_{}", i),
+        ));
+        solver.add_constraint(Box::new(SimpleArithmeticConstraint::new(
+            diag1[i].clone(),
+            vars[i].clone(),
+            i as i64,
+            false,
+        )));
+        solver.add_constraint(Box::new(SimpleArithmeticConstraint::new(
+            diag2[i].clone(),
+            vars[i].clone(),
+            -(i as i64),
+            false,
+        )));
+    }
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(diag1)));
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(diag2)));
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    vars.iter().map(|v| v.borrow().value()).collect()
+}
+
+fn assert_nqueens_solution(pos: &[i64]) {
+    for i in 0..pos.len() {
+        for j in 0..i {
+            assert_ne!(pos[i], pos[j]);
+            assert_ne!((pos[i] - pos[j]).abs(), (i - j) as i64);
+        }
+    }
+}
+
+#[test]
+fn test_config_presets() {
+    assert_nqueens_solution(&solve_nqueens(Config::default_cp(), 8));
+    assert_nqueens_solution(&solve_nqueens(Config::dom_wdeg_restart(7), 8));
+    // the lexicographically smallest placement comes first
+    let first = solve_nqueens(Config::enumerate_all(), 8);
+    assert_eq!(first, vec![0, 4, 7, 5, 2, 6, 1, 3]);
+}
+
+// counts the calls of an AllDifferentACPropagator
+struct CountingAllDifferent {
+    inner: AllDifferentACPropagator,
+    calls: usize,
+}
+
+impl Propagator for CountingAllDifferent {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.inner.listen(self_pointer);
+    }
+
+    fn propagate(&mut self) {
+        self.calls += 1;
+        self.inner.propagate();
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        self.inner.get_cb()
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        self.inner.get_cb_mut()
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.inner.is_idempotent()
+    }
+
+    fn priority(&self) -> u8 {
+        self.inner.priority()
+    }
+}
+
+#[test]
+fn test_nqueens_matching_repair() {
+    let n = 8;
+    let mut solver = Solver::with_config(Config::default_cp());
+    let mut groups = vec![Vec::new(), Vec::new(), Vec::new()];
+    for i in 0..n {
+        let pos = solver.new_variable(0, (n as i64) - 1, format!("pos_{}", i));
+        let diag1 = solver.new_variable(i as i64, (n + i - 1) as i64, format!("+diag_{}", i));
+        let diag2 = solver.new_variable(
+            -(i as i64),
+            (n as i64) - 1 - (i as i64),
+            format!("-diag_{}", i),
+        );
+        solver.add_constraint(Box::new(SimpleArithmeticConstraint::new(
+            diag1.clone(),
+            pos.clone(),
+            i as i64,
+            false,
+        )));
+        solver.add_constraint(Box::new(SimpleArithmeticConstraint::new(
+            diag2.clone(),
+            pos.clone(),
+            -(i as i64),
+            false,
+        )));
+        groups[0].push(pos);
+        groups[1].push(diag1);
+        groups[2].push(diag2);
+    }
+    let props: Vec<_> = groups
+        .into_iter()
+        .map(|vars| {
+            let p = Rc::new(RefCell::new(CountingAllDifferent {
+                inner: AllDifferentACPropagator::new(vars, solver.new_propagator_id()),
+                calls: 0,
+            }));
+            solver.add_propagator(p.clone());
+            p.borrow().listen(p.clone());
+            p
+        })
+        .collect();
+    assert_eq!(solver.count_solutions(), 92);
+    // a matching built from scratch needs n augmenting paths at every call
+    for p in props {
+        let p = p.borrow();
+        assert!(p.inner.augmentations() < p.calls * n / 2);
+    }
+}
+
+#[test]
+fn test_propagation_cap() {
+    for cap in [1, 3] {
+        let config = Config {
+            max_propagations_per_node: Some(cap),
+            ..Config::default()
+        };
+        assert_nqueens_solution(&solve_nqueens(config, 6));
+    }
+}
+
+#[test]
+fn test_deterministic_first_solution() {
+    // with a propagation cap, the solution found depends on the order propagators are woken up in
+    let solve = || {
+        solve_nqueens(
+            Config {
+                max_propagations_per_node: Some(3),
+                ..Config::default()
+            },
+            6,
+        )
+    };
+    let first = solve();
+    for _ in 0..5 {
+        assert_eq!(solve(), first);
+    }
+}
+
+#[test]
+fn test_value_order_selectors() {
+    let priority = vec![2, 0, 1];
+    let mut solver = Solver::new(
+        Box::new(ValueOrderVariableSelector::new(priority.clone())),
+        Box::new(ValueOrderValueSelector::new(priority)),
+    );
+    // path 0 - 1 - 2
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 2, format!("color_{}", i)))
+        .collect();
+    for i in 0..2 {
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+            vars[i].clone(),
+            vars[i + 1].clone(),
+        ])));
+    }
+    assert!(solver.solve());
+    // color 2 is placed everywhere possible before color 0 is tried
+    let colors: Vec<i64> = vars.iter().map(|v| v.borrow().value()).collect();
+    assert_eq!(colors, vec![2, 0, 2]);
+}
+
+#[test]
+fn test_median_value_selector() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MedianValueSelector {}),
+    );
+    let x = solver.new_variable(0, 9, "x".to_string());
+    // (0 + 9) / 2 = 4 is a hole, the median of {0, 1, 2, 7, 8, 9} is 2
+    for val in 3..=6 {
+        x.borrow_mut().remove(val);
+    }
+    assert_eq!(MedianValueSelector {}.select(x.borrow().domain.as_ref()), 2);
+    assert!(solver.solve());
+    assert_eq!(x.borrow().value(), 2);
+}
+
+#[test]
+fn test_custom_order_value_selector() {
+    // values closest to 2 first
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(CustomOrderValueSelector::new(|dom: &dyn Domain| {
+            let mut values: Vec<i64> = dom.iter().collect();
+            values.sort_by_key(|x| (x - 2).abs());
+            values
+        })),
+    );
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 4, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    assert!(solver.solve());
+    // 2, then 1 and 3 at distance 1, ties keep the increasing order of the domain
+    let values: Vec<i64> = vars.iter().map(|v| v.borrow().value()).collect();
+    assert_eq!(values, vec![2, 1, 3]);
+}
+
+#[test]
+fn test_binary_search_extreme_bounds() {
+    for opt in [-5, 42] {
+        // there is a solution with value <= x iff x >= opt
+        let create_solver = |x: i64| {
+            let mut solver = Solver::with_config(Config::default());
+            let v = solver.const_variable(opt);
+            solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+                vec![v],
+                vec![1],
+                x,
+            )));
+            solver
+        };
+        assert_eq!(binary_search_optimizer(create_solver, i64::MIN, i64::MAX), opt);
+    }
+}
+
+#[test]
+fn test_proof_time_limit() {
+    for limit in [None, Some(Duration::ZERO)] {
+        let mut config = Config::new(
+            Box::new(FirstFailVariableSelector {}),
+            Box::new(MaxValueSelector {}),
+        );
+        config.proof_time_limit = limit;
+        let mut solver = Solver::with_config(config);
+        let vars: Vec<_> = (0..3)
+            .map(|i| solver.new_variable(0, 5, format!("var_{}", i)))
+            .collect();
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+        solver.add_objective(Box::new(SumObjective { vars: vars.clone() }));
+        assert!(solver.solve());
+        assert!(solver.check_solution());
+        let sum: i64 = vars.iter().map(|v| v.borrow().value()).sum();
+        assert_eq!(sum, solver.get_objective());
+        if limit.is_none() {
+            assert!(solver.search_completed());
+            assert_eq!(sum, 3);
+            assert_eq!(solver.objective_lower_bound(), 3);
+        } else {
+            // search stops right after the first solution, 5 + 4 + 3
+            assert!(!solver.search_completed());
+            assert_eq!(sum, 12);
+            // the optimum is 3, so the bound can not be above it
+            assert!(solver.objective_lower_bound() <= 3);
+        }
+    }
+}
+
+#[test]
+fn test_reify_helpers() {
+    let mut solver = Solver::with_config(Config::default());
+    let x: Vec<_> = (0..4)
+        .map(|i| solver.new_variable(0, 3, format!("x_{}", i)))
+        .collect();
+    x[0].borrow_mut().assign(0);
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(x.clone())));
+    let ascents: Vec<_> = (0..3)
+        .map(|i| reify_lt(&mut solver, x[i].clone(), x[i + 1].clone()))
+        .collect();
+    let le = reify_le(&mut solver, x[1].clone(), x[2].clone());
+    let eq = reify_eq(&mut solver, x[0].clone(), x[3].clone());
+    solver.add_objective(Box::new(SumObjective {
+        vars: ascents.clone(),
+    }));
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    // x_0 = 0 forces one ascent, the rest can descend: 0 3 2 1
+    assert_eq!(solver.get_objective(), 1);
+    let values: Vec<_> = x.iter().map(|v| v.borrow().value()).collect();
+    assert_eq!(values, vec![0, 3, 2, 1]);
+    assert_eq!(le.borrow().value(), 0);
+    assert_eq!(eq.borrow().value(), 0);
+}
+
+#[test]
+fn test_alldifferent_sum_bound() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let vars: Vec<_> = [(2, 9), (0, 9), (2, 9), (1, 9), (7, 9)]
+        .iter()
+        .enumerate()
+        .map(|(i, (lb, ub))| solver.new_variable(*lb, *ub, format!("var_{}", i)))
+        .collect();
+    let bound = AllDifferentSumBound::new(vars.clone());
+    // 0 + 1 + 2 + 3 + 7
+    assert_eq!(bound.bound(), 13);
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    solver.add_objective_bound(Box::new(bound));
+    solver.add_objective(Box::new(SumObjective { vars }));
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), 13);
+}
+
+#[test]
+fn test_circuit() {
+    let n = 6;
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let succ: Vec<_> = (0..n)
+        .map(|i| solver.new_variable(0, n - 1, format!("succ_{}", i)))
+        .collect();
+    // only edges between vertices at distance 1 or 2 on a cycle
+    for (i, v) in succ.iter().enumerate() {
+        for j in 0..n {
+            let d = (i as i64 - j).rem_euclid(n);
+            if d != 1 && d != 2 && d != n - 1 && d != n - 2 {
+                v.borrow_mut().remove(j);
+            }
+        }
+    }
+    solver.add_constraint(Box::new(CircuitConstraint::new(succ.clone())));
+    assert!(solver.solve());
+    let mut v = 0;
+    let mut visited = vec![false; n as usize];
+    for _ in 0..n {
+        v = succ[v].borrow().value() as usize;
+        assert!(!visited[v]);
+        visited[v] = true;
+    }
+    assert_eq!(v, 0);
+}
+
+#[test]
+fn test_objective_lower_bound() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let vars: Vec<_> = (0..4)
+        .map(|i| solver.new_variable(0, 6, format!("var_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    solver.add_objective(Box::new(SumObjective { vars }));
+    assert!(solver.solve());
+    // the gap closes at optimality
+    assert_eq!(solver.get_objective(), 6);
+    assert_eq!(solver.objective_lower_bound(), 6);
+
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 1, "x".to_string());
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone()],
+        vec![1],
+        -1,
+    )));
+    solver.add_objective(Box::new(SumObjective { vars: vec![x] }));
+    assert!(!solver.solve());
+    assert_eq!(solver.objective_lower_bound(), i64::MAX);
+}
+
+#[test]
+fn test_linear_objective() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 5, "x".to_string());
+    let y = solver.new_variable(0, 5, "y".to_string());
+    let z = solver.new_variable(0, 5, "z".to_string());
+    // y - x <= 2
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![-1, 1],
+        2,
+    )));
+    let objective = LinearObjective::new(vec![x.clone(), y.clone(), z.clone()], vec![2, -3, 1]);
+    // z at lb, y at ub
+    assert_eq!(objective.bound(), -15);
+    solver.add_objective(Box::new(objective));
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), -9);
+    assert_eq!((x.borrow().value(), y.borrow().value(), z.borrow().value()), (3, 5, 0));
+}
+
+#[test]
+fn test_lex_objective() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    // x + y >= 3
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![-1, -1],
+        -3,
+    )));
+    // minimize x + y, then maximize x among the ties
+    solver.add_objective(Box::new(LexObjective::new(vec![
+        Box::new(LinearObjective::new(vec![x.clone(), y.clone()], vec![1, 1])),
+        Box::new(LinearObjective::new(vec![x.clone()], vec![-1])),
+    ])));
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), 3);
+    assert_eq!(solver.get_objective_values(), &[3, -3]);
+    assert_eq!((x.borrow().value(), y.borrow().value()), (3, 0));
+    assert_eq!(solver.objective_lower_bound(), 3);
+}
+
+// pigeonhole with pairwise constraints only, so infeasibility is proven by search
+// the full search needs 120 failures
+fn solve_pigeonhole(config: Config) -> (bool, SearchStats) {
+    let mut solver = Solver::with_config(config);
+    let vars: Vec<_> = (0..6)
+        .map(|i| solver.new_variable(0, 4, format!("x_{}", i)))
+        .collect();
+    for i in 0..6 {
+        for j in i + 1..6 {
+            solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+                vars[i].clone(),
+                vars[j].clone(),
+            ])));
+        }
+    }
+    assert!(!solver.solve());
+    (solver.search_completed(), solver.get_stats().clone())
+}
+
+#[test]
+fn test_luby_restarts() {
+    let (completed, stats) = solve_pigeonhole(Config {
+        restart_strategy: RestartStrategy::Luby { base: 2 },
+        ..Config::default()
+    });
+    // restarts repeat the same search, and still prove infeasibility
+    assert!(completed);
+    assert!(stats.fails > 120);
+
+    let mut solver = Solver::with_config(Config {
+        restart_strategy: RestartStrategy::Luby { base: 1 },
+        ..Config::default()
+    });
+    let vars: Vec<_> = (0..4)
+        .map(|i| solver.new_variable(0, 6, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    solver.add_objective(Box::new(SumObjective { vars }));
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), 6);
+}
+
+#[test]
+#[should_panic]
+fn test_luby_restarts_zero_base() {
+    solve_pigeonhole(Config {
+        restart_strategy: RestartStrategy::Luby { base: 0 },
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_geometric_restarts() {
+    let (completed, stats) = solve_pigeonhole(Config {
+        restart_strategy: RestartStrategy::Geometric {
+            base: 2,
+            factor: 1.5,
+        },
+        ..Config::default()
+    });
+    assert!(completed);
+    assert!(stats.restarts > 0);
+    let (_, stats) = solve_pigeonhole(Config::default());
+    assert_eq!(stats.restarts, 0);
+
+    let first = solve_nqueens(
+        Config {
+            value_selector: Box::new(RandomValueSelector::new(3)),
+            restart_strategy: RestartStrategy::Geometric {
+                base: 1,
+                factor: 2.0,
+            },
+            ..Config::default()
+        },
+        8,
+    );
+    assert_nqueens_solution(&first);
+}
+
+#[test]
+#[should_panic]
+fn test_geometric_restarts_constant_limit() {
+    solve_pigeonhole(Config {
+        restart_strategy: RestartStrategy::Geometric {
+            base: 2,
+            factor: 1.0,
+        },
+        ..Config::default()
+    });
+}
+
+#[test]
+fn test_node_and_fail_limits() {
+    let (completed, stats) = solve_pigeonhole(Config::default());
+    assert!(completed && !stats.limit_reached);
+    assert_eq!(stats.fails, 120);
+
+    let (completed, stats) = solve_pigeonhole(Config {
+        node_limit: Some(10),
+        ..Config::default()
+    });
+    assert!(!completed && stats.limit_reached);
+    assert_eq!(stats.nodes, 10);
+
+    let (completed, stats) = solve_pigeonhole(Config {
+        fail_limit: Some(5),
+        ..Config::default()
+    });
+    assert!(!completed && stats.limit_reached);
+    assert_eq!(stats.fails, 5);
+}
+
+#[test]
+fn test_count_solutions() {
+    let count_permutations = |config: Config| {
+        let mut solver = Solver::with_config(config);
+        let vars: Vec<_> = (0..4)
+            .map(|i| solver.new_variable(0, 3, format!("x_{}", i)))
+            .collect();
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vars)));
+        let count = solver.count_solutions();
+        (count, solver.get_stats().clone())
+    };
+    let (count, stats) = count_permutations(Config::default());
+    assert_eq!(count, 24);
+    assert!(stats.whole_tree_explored);
+
+    let (count, stats) = count_permutations(Config {
+        node_limit: Some(10),
+        ..Config::default()
+    });
+    assert!(count < 24);
+    assert!(stats.limit_reached && !stats.whole_tree_explored);
+}
+
+#[test]
+fn test_time_limit() {
+    // pigeonhole with 12 variables and 11 values, far too many nodes to finish
+    let mut solver = Solver::with_config(Config {
+        time_limit: Some(Duration::from_millis(10)),
+        ..Config::default()
+    });
+    let vars: Vec<_> = (0..12)
+        .map(|i| solver.new_variable(0, 10, format!("x_{}", i)))
+        .collect();
+    for i in 0..12 {
+        for j in i + 1..12 {
+            solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+                vars[i].clone(),
+                vars[j].clone(),
+            ])));
+        }
+    }
+    let start = Instant::now();
+    assert!(!solver.solve());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(!solver.search_completed());
+    assert!(solver.get_stats().timed_out && solver.get_stats().limit_reached);
+}
+
+#[test]
+fn test_random_value_selector() {
+    let mut solver = Solver::with_config(Config::default());
+    let x = solver.new_variable(0, 9, "x".to_string());
+    for val in [1, 4, 5, 8] {
+        x.borrow_mut().remove(val);
+    }
+    let selector = RandomValueSelector::new(7);
+    let mut hits = [0; 10];
+    for _ in 0..600 {
+        hits[selector.select(x.borrow().domain.as_ref()) as usize] += 1;
+    }
+    for (val, h) in hits.iter().enumerate() {
+        assert_eq!(*h > 0, x.borrow().possible(val as i64));
+    }
+
+    // restarts with random values, the same seed gives the same solution
+    let config = |seed| Config {
+        value_selector: Box::new(RandomValueSelector::new(seed)),
+        restart_strategy: RestartStrategy::Luby { base: 4 },
+        ..Config::default()
+    };
+    let first = solve_nqueens(config(1), 8);
+    assert_nqueens_solution(&first);
+    assert_eq!(solve_nqueens(config(1), 8), first);
+}
+
+#[test]
+fn test_random_variable_selectors() {
+    let mut solver = Solver::with_config(Config::default());
+    let vars: Vec<_> = (0..4)
+        .map(|i| solver.new_variable(0, 3, format!("x_{}", i)))
+        .collect();
+    // x_1 and x_3 have the smallest domains
+    vars[1].borrow_mut().remove(0);
+    vars[3].borrow_mut().remove(2);
+    let index = |v: &Rc<RefCell<Variable>>| vars.iter().position(|u| Rc::ptr_eq(u, v)).unwrap();
+    let selectors: [(Box<dyn VariableSelector>, Vec<bool>); 2] = [
+        (Box::new(RandomVariableSelector::new(3)), vec![true; 4]),
+        (
+            Box::new(FirstFailRandomTieVariableSelector::new(3)),
+            vec![false, true, false, true],
+        ),
+    ];
+    for (selector, expected) in selectors {
+        let mut hits = [0; 4];
+        for _ in 0..200 {
+            hits[index(&selector.select(vars.clone()))] += 1;
+        }
+        let picked: Vec<bool> = hits.iter().map(|h| *h > 0).collect();
+        assert_eq!(picked, expected);
+    }
+}
+
+#[test]
+fn test_dom_wdeg() {
+    let mut solver = Solver::with_config(Config::default());
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 5, format!("x_{}", i)))
+        .collect();
+    vars[0].borrow_mut().set_ub(2);
+    vars[1].borrow_mut().weight = 2;
+    // 6 / 3 < 3 / 1
+    let selected = DomWDegVariableSelector {}.select(vars.clone());
+    assert!(Rc::ptr_eq(&selected, &vars[1]));
+
+    let (completed, stats) = solve_pigeonhole(Config {
+        variable_selector: Box::new(DomWDegVariableSelector {}),
+        ..Config::default()
+    });
+    assert!(completed);
+    assert!(stats.fails > 0);
+
+    // failing constraints make their variables heavier
+    let mut solver = Solver::with_config(Config {
+        variable_selector: Box::new(DomWDegVariableSelector {}),
+        ..Config::default()
+    });
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    let z = solver.new_variable(0, 3, "z".to_string());
+    // x + y <= 2, x + y >= 3
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![1, 1],
+        2,
+    )));
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![-1, -1],
+        -3,
+    )));
+    assert!(!solver.solve());
+    assert!(x.borrow().weight > 0 && y.borrow().weight > 0);
+    assert_eq!(z.borrow().weight, 0);
+}
+
+#[test]
+fn test_on_solution() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let log = seen.clone();
+    let mut solver = Solver::with_config(Config {
+        on_solution: Some(Box::new(move |vars: &[Rc<RefCell<Variable>>]| {
+            log.borrow_mut()
+                .push(vars.iter().map(|v| v.borrow().value()).collect::<Vec<_>>());
+        })),
+        ..Config::default()
+    });
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 2, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    assert_eq!(solver.count_solutions(), 6);
+    assert_eq!(seen.borrow().len(), 6);
+    assert!(seen.borrow().iter().all(|s| s.len() == 3));
+
+    // optimization reports the improving solutions only, the last one is the optimum
+    seen.borrow_mut().clear();
+    solver.add_objective(Box::new(SumObjective {
+        vars: vec![vars[0].clone()],
+    }));
+    assert!(solver.solve());
+    assert_eq!(solver.get_objective(), 0);
+    let log = seen.borrow();
+    assert!(!log.is_empty());
+    assert_eq!(log.last().unwrap()[0], 0);
+    assert!(log.windows(2).all(|w| w[1][0] < w[0][0]));
+}
+
+#[test]
+fn test_stats_handle() {
+    let mut solver = Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    );
+    let stats = solver.stats_handle();
+    let vars: Vec<_> = (0..4)
+        .map(|i| solver.new_variable(0, 3, format!("x_{}", i)))
+        .collect();
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+    // the objective is evaluated inside the search, where the counters are live
+    struct DepthProbe {
+        vars: Vec<Rc<RefCell<Variable>>>,
+        stats: Rc<RefCell<SearchStats>>,
+        depths: Rc<RefCell<Vec<usize>>>,
+    }
+    impl ObjectiveFunction for DepthProbe {
+        fn eval(&self) -> i64 {
+            self.depths.borrow_mut().push(self.stats.borrow().depth);
+            -self.vars[0].borrow().value()
+        }
+        fn bound(&self) -> i64 {
+            -self.vars[0].borrow().get_ub()
+        }
+    }
+    let depths = Rc::new(RefCell::new(Vec::new()));
+    solver.add_objective(Box::new(DepthProbe {
+        vars: vars.clone(),
+        stats: stats.clone(),
+        depths: depths.clone(),
+    }));
+    assert!(solver.solve());
+    assert!(depths.borrow().iter().all(|d| *d > 0));
+    let snapshot = stats.borrow().clone();
+    assert_eq!(snapshot.depth, 0);
+    assert!(snapshot.max_depth >= *depths.borrow().iter().max().unwrap());
+    assert_eq!(snapshot.nodes, solver.get_stats().nodes);
+    assert!(snapshot.nodes > 0);
+}
+
+#[test]
+fn test_objective_cut() {
+    let minimize = |linear: bool| {
+        let mut solver = Solver::with_config(Config::default());
+        let vars: Vec<_> = (0..5)
+            .map(|i| solver.new_variable(0, 8, format!("x_{}", i)))
+            .collect();
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vars.clone())));
+        // x_0 + x_1 >= 9 keeps the first solutions away from the optimum
+        solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+            vec![vars[0].clone(), vars[1].clone()],
+            vec![-1, -1],
+            -9,
+        )));
+        if linear {
+            solver.add_objective(Box::new(LinearObjective::new(vars.clone(), vec![1; 5])));
+        } else {
+            solver.add_objective(Box::new(SumObjective { vars }));
+        }
+        assert!(solver.solve());
+        assert!(solver.check_solution());
+        (solver.get_objective(), solver.get_stats().nodes)
+    };
+    let (bounded, bounded_nodes) = minimize(false);
+    let (posted, posted_nodes) = minimize(true);
+    assert_eq!(bounded, posted);
+    // the posted sum constraint prunes the other variables, the bound only fails nodes
+    assert!(posted_nodes < bounded_nodes);
+
+    let x = Rc::new(RefCell::new(Variable::new(
+        Rc::new(RefCell::new(SolverState::new())),
+        2,
+        2,
+        "x".to_string(),
+    )));
+    let objective = LinearObjective::new(vec![x], vec![3]);
+    // the cut follows its bound
+    let bound = Rc::new(std::cell::Cell::new(6));
+    let cut = objective.as_constraint(bound.clone()).unwrap();
+    assert!(cut.satisfied());
+    bound.set(5);
+    assert!(!cut.satisfied());
+}
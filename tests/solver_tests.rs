@@ -0,0 +1,657 @@
+use ezcp::alldifferent::AllDifferentConstraint;
+use ezcp::arithmetic::SimpleArithmeticConstraint;
+use ezcp::config::Config;
+use ezcp::constraint::Constraint;
+use ezcp::events::Event;
+use ezcp::gcc::GlobalCardinalityConstraint;
+use ezcp::lex::LexLessConstraint;
+use ezcp::linear::LinearInequalityConstraint;
+use ezcp::logic::{AndConstraint, NegateConstraint, OrConstraint};
+use ezcp::objective_function::{LinearObjective, ObjectiveFunction};
+use ezcp::propagator::{
+    Propagator, PropagatorControlBlock, PRIORITY_HIGH, PRIORITY_LOW, PRIORITY_MEDIUM,
+};
+use ezcp::reify::{IfThenElseConstraint, ReifiedConstraint};
+use ezcp::scheduling::OverlapPair;
+use ezcp::solver::Solver;
+use ezcp::table::TableConstraint;
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::{BoolVar, DomainKind, Variable};
+use ezcp::variable_selector::{DomWDegVariableSelector, FirstFailVariableSelector};
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn new_solver() -> Solver {
+    Solver::new(
+        Box::new(FirstFailVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    )
+}
+
+#[test]
+fn test_variable_lookup() {
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 5, "x".to_string());
+    assert!(solver.has_variable("x"));
+    assert!(!solver.has_variable("y"));
+    assert!(Rc::ptr_eq(&solver.get_variable_by_name("x").unwrap(), &x));
+    assert!(solver.get_variable_by_name("y").is_none());
+}
+
+#[test]
+fn test_const_variable() {
+    let mut solver = new_solver();
+    let a = solver.const_variable(7);
+    let b = solver.const_variable(7);
+    let c = solver.const_variable(-3);
+    assert!(Rc::ptr_eq(&a, &b));
+    assert!(!Rc::ptr_eq(&a, &c));
+    assert_eq!(a.borrow().value(), 7);
+    assert_eq!(c.borrow().value(), -3);
+    assert!(solver.solve());
+}
+
+#[test]
+fn test_root_propagation() {
+    // constants never fire events, so only the first pass at the root sees the violation
+    let mut solver = new_solver();
+    let x = solver.const_variable(3);
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x],
+        vec![1],
+        2,
+    )));
+    assert!(!solver.solve());
+}
+
+#[test]
+fn test_add_constraints() {
+    let mut solver = new_solver();
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 2, format!("x{}", i)))
+        .collect();
+    solver.add_constraints(vec![
+        Box::new(AllDifferentConstraint::new(vars.clone())),
+        // x0 - x1 <= -1
+        Box::new(LinearInequalityConstraint::new(
+            vec![vars[0].clone(), vars[1].clone()],
+            vec![1, -1],
+            -1,
+        )),
+        // x1 - x2 <= -1
+        Box::new(LinearInequalityConstraint::new(
+            vec![vars[1].clone(), vars[2].clone()],
+            vec![1, -1],
+            -1,
+        )),
+    ]);
+    assert!(solver.solve());
+    assert!(solver.check_solution());
+    for (i, v) in vars.iter().enumerate() {
+        assert_eq!(v.borrow().value(), i as i64);
+    }
+}
+
+// x = 1, but without any propagator enforcing it
+struct UnpropagatedConstraint {
+    x: Rc<RefCell<Variable>>,
+}
+
+impl Constraint for UnpropagatedConstraint {
+    fn satisfied(&self) -> bool {
+        self.x.borrow().try_value() == Some(1)
+    }
+
+    fn create_propagators(&self, _solver: &mut Solver) {}
+
+    fn variables(&self) -> Vec<Rc<RefCell<Variable>>> {
+        vec![self.x.clone()]
+    }
+}
+
+// x + y <= 1 without a scope
+struct ScopelessConstraint {
+    x: Rc<RefCell<Variable>>,
+    y: Rc<RefCell<Variable>>,
+}
+
+impl Constraint for ScopelessConstraint {
+    fn satisfied(&self) -> bool {
+        match (self.x.borrow().try_value(), self.y.borrow().try_value()) {
+            (Some(x), Some(y)) => x + y <= 1,
+            _ => false,
+        }
+    }
+
+    fn create_propagators(&self, solver: &mut Solver) {
+        LinearInequalityConstraint::new(vec![self.x.clone(), self.y.clone()], vec![1, 1], 1)
+            .create_propagators(solver);
+    }
+}
+
+#[test]
+fn test_constraint_without_scope() {
+    let mut solver = Solver::with_config(Config::new(
+        Box::new(DomWDegVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    ));
+    let x = solver.new_variable(1, 2, "x".to_string());
+    let y = solver.new_variable(0, 2, "y".to_string());
+    solver.add_constraint(Box::new(ScopelessConstraint {
+        x: x.clone(),
+        y: y.clone(),
+    }));
+    assert!(solver.dump_model().ends_with("constraint ScopelessConstraint()\n"));
+    assert!(solver.solve());
+    assert_eq!((x.borrow().value(), y.borrow().value()), (1, 0));
+    // failures of the constraint are not counted for its variables
+    let mut solver = new_solver();
+    let x = solver.new_variable(1, 2, "x".to_string());
+    let y = solver.new_variable(1, 2, "y".to_string());
+    solver.add_constraint(Box::new(ScopelessConstraint {
+        x: x.clone(),
+        y: y.clone(),
+    }));
+    assert!(!solver.solve());
+    assert_eq!((x.borrow().weight, y.borrow().weight), (0, 0));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "constraint #0 is not satisfied")]
+fn test_unpropagated_constraint_detected() {
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    solver.add_constraint(Box::new(UnpropagatedConstraint { x }));
+    solver.solve();
+}
+
+#[test]
+fn test_values_after_solve() {
+    struct Negated {
+        x: Rc<RefCell<Variable>>,
+    }
+
+    impl ObjectiveFunction for Negated {
+        fn eval(&self) -> i64 {
+            -self.x.borrow().value()
+        }
+
+        fn bound(&self) -> i64 {
+            -self.x.borrow().get_ub()
+        }
+    }
+
+    // satisfaction: the first solution stays assigned
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![-1, -1],
+        -5,
+    )));
+    assert!(solver.solve());
+    assert!(x.borrow().is_assigned() && y.borrow().is_assigned());
+    assert!(solver.check_solution());
+
+    // optimization: variables hold the best solution, not the last one visited
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![x.clone(), y.clone()])));
+    solver.add_objective(Box::new(Negated { x: x.clone() }));
+    assert!(solver.solve());
+    assert_eq!(x.borrow().value(), 3);
+    assert_eq!(-x.borrow().value(), solver.get_objective());
+    assert!(solver.check_solution());
+
+    // failure: domains are restored
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    let y = solver.new_variable(0, 1, "y".to_string());
+    let z = solver.new_variable(0, 1, "z".to_string());
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+        x.clone(),
+        y.clone(),
+        z.clone(),
+    ])));
+    assert!(!solver.solve());
+    for v in [x, y, z] {
+        assert_eq!(v.borrow().size(), 2);
+    }
+}
+
+#[test]
+fn test_constraint_variables() {
+    let n = 5;
+    let mut solver = new_solver();
+    let mut constraints = Vec::<Box<dyn Constraint>>::new();
+    let mut pos = Vec::new();
+    let mut diag1 = Vec::new();
+    let mut diag2 = Vec::new();
+    for i in 0..n {
+        pos.push(solver.new_variable(0, n - 1, format!("pos_{}", i)));
+        diag1.push(solver.new_variable(i, n + i - 1, format!("+diag_{}", i)));
+        diag2.push(solver.new_variable(-i, n - 1 - i, format!("-diag_{}", i)));
+        let (x, d1, d2) = (&pos[i as usize], &diag1[i as usize], &diag2[i as usize]);
+        constraints.push(Box::new(SimpleArithmeticConstraint::new(d1.clone(), x.clone(), i, false)));
+        constraints.push(Box::new(SimpleArithmeticConstraint::new(d2.clone(), x.clone(), -i, false)));
+    }
+    for vars in [&pos, &diag1, &diag2] {
+        constraints.push(Box::new(AllDifferentConstraint::new(vars.clone())));
+    }
+    // variable -> indices of constraints involving it
+    let mut incidence = HashMap::<String, Vec<usize>>::new();
+    for (i, c) in constraints.iter().enumerate() {
+        for v in c.variables() {
+            incidence.entry(v.borrow().name.clone()).or_default().push(i);
+        }
+    }
+    let last = constraints.len() - 1;
+    for i in 0..n as usize {
+        assert_eq!(incidence[&format!("pos_{}", i)], vec![2 * i, 2 * i + 1, last - 2]);
+        assert_eq!(incidence[&format!("+diag_{}", i)], vec![2 * i, last - 1]);
+        assert_eq!(incidence[&format!("-diag_{}", i)], vec![2 * i + 1, last]);
+    }
+    solver.add_constraints(constraints);
+    assert!(solver.solve());
+}
+
+#[test]
+fn test_dump_model() {
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 9, "x".to_string());
+    let y = solver.new_variable(-2, 2, "y".to_string());
+    let z = solver.const_variable(4);
+    for val in [3, 4, 5, 8] {
+        x.borrow_mut().remove(val);
+    }
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![
+        x.clone(),
+        y.clone(),
+        z.clone(),
+    ])));
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x, y],
+        vec![1, -1],
+        3,
+    )));
+    assert_eq!(
+        solver.dump_model(),
+        "var x in {0..2, 6..7, 9}\n\
+         var y in {-2..2}\n\
+         var 4 in {4}\n\
+         constraint AllDifferentConstraint(x, y, 4)\n\
+         constraint LinearInequalityConstraint(x, y)\n"
+    );
+}
+
+#[test]
+fn test_variable_with_ranges() {
+    let mut solver = new_solver();
+    let x = solver.new_variable_with_ranges(&[(1, 3), (7, 8), (12, 12)], "x".to_string());
+    assert!(Rc::ptr_eq(&solver.get_variable_by_name("x").unwrap(), &x));
+    assert_eq!(solver.dump_model(), "var x in {1..3, 7..8, 12}\n");
+    // x is even and greater than 2
+    for val in [1, 3, 7] {
+        x.borrow_mut().remove(val);
+    }
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone()],
+        vec![-1],
+        -3,
+    )));
+    assert!(solver.solve());
+    assert_eq!(x.borrow().value(), 8);
+}
+
+#[test]
+fn test_huge_domain() {
+    let mut solver = new_solver();
+    let x = solver.new_variable_with_kind(0, 1_000_000_000, DomainKind::Interval, "x".to_string());
+    let y = solver.new_variable_with_kind(0, 1000, DomainKind::Interval, "y".to_string());
+    // x + y >= 999_999_999
+    solver.add_constraint(Box::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![-1, -1],
+        -999_999_999,
+    )));
+    assert_eq!(
+        solver.dump_model(),
+        "var x in {0..1000000000}\n\
+         var y in {0..1000}\n\
+         constraint LinearInequalityConstraint(x, y)\n"
+    );
+    // debug builds print every domain at each search node, so x is narrowed first
+    assert!(solver.propagate_all());
+    assert_eq!(x.borrow().get_lb(), 999_998_999);
+    assert!(solver.solve());
+    assert!(x.borrow().value() + y.borrow().value() >= 999_999_999);
+    // wide domains are bitsets unless IntervalDomain is asked for
+    let z = new_solver().new_variable(0, 100_000, "z".to_string());
+    assert!(z.borrow().domain.bitmap().is_some());
+}
+
+#[test]
+fn test_solve_all() {
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    let y = solver.new_variable(0, 1, "y".to_string());
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![x.clone(), y.clone()])));
+    let mut solutions = solver.solve_all();
+    solutions.sort();
+    assert_eq!(solutions, vec![vec![0, 1], vec![1, 0]]);
+    // domains are restored after enumeration
+    assert!(!x.borrow().is_assigned());
+    assert!(!y.borrow().is_assigned());
+
+    // maximizing x, every improving solution is reported
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 3, "x".to_string());
+    solver.add_objective(Box::new(LinearObjective::new(vec![x.clone()], vec![-1])));
+    assert_eq!(solver.solve_all(), vec![vec![0], vec![1], vec![2], vec![3]]);
+    assert_eq!(x.borrow().value(), 3);
+}
+
+// records its id when it runs
+struct LoggingPropagator {
+    pcb: PropagatorControlBlock,
+    log: Rc<RefCell<Vec<usize>>>,
+}
+
+impl Propagator for LoggingPropagator {
+    fn listen(&self, _self_pointer: Rc<RefCell<dyn Propagator>>) {}
+
+    fn propagate(&mut self) {
+        self.log.borrow_mut().push(self.pcb.id);
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+}
+
+// lowers the upper bound of x by one, waking itself up
+struct ShrinkingPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    idempotent: bool,
+    runs: usize,
+}
+
+impl Propagator for ShrinkingPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.x
+            .borrow_mut()
+            .add_listener(self_pointer, Event::Modified);
+    }
+
+    fn propagate(&mut self) {
+        self.runs += 1;
+        let (lb, ub) = (self.x.borrow().get_lb(), self.x.borrow().get_ub());
+        if lb < ub {
+            self.x.borrow_mut().set_ub(ub - 1);
+        }
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+
+    fn is_idempotent(&self) -> bool {
+        self.idempotent
+    }
+}
+
+#[test]
+fn test_idempotent_not_requeued() {
+    for (idempotent, runs, ub) in [(true, 1, 2), (false, 4, 0)] {
+        let mut solver = new_solver();
+        let x = solver.new_variable(0, 3, "x".to_string());
+        let p = Rc::new(RefCell::new(ShrinkingPropagator {
+            pcb: PropagatorControlBlock::new(solver.new_propagator_id()),
+            x: x.clone(),
+            idempotent,
+            runs: 0,
+        }));
+        solver.add_propagator(p.clone());
+        p.borrow().listen(p.clone());
+        assert!(solver.propagate_all());
+        assert_eq!(p.borrow().runs, runs);
+        assert_eq!(x.borrow().get_ub(), ub);
+    }
+}
+
+#[test]
+fn test_propagator_priority() {
+    let mut solver = new_solver();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for priority in [PRIORITY_LOW, PRIORITY_MEDIUM, PRIORITY_HIGH, PRIORITY_LOW, PRIORITY_HIGH] {
+        let mut pcb = PropagatorControlBlock::new(solver.new_propagator_id());
+        pcb.priority = priority;
+        solver.add_propagator(Rc::new(RefCell::new(LoggingPropagator {
+            pcb,
+            log: log.clone(),
+        })));
+    }
+    assert!(solver.propagate_all());
+    // first by priority, then in the order of queueing
+    assert_eq!(*log.borrow(), vec![2, 4, 1, 0, 3]);
+}
+
+#[test]
+fn test_if_then_else() {
+    let model = |solver: &mut Solver| {
+        let b = solver.new_variable(0, 1, "b".to_string());
+        let x = solver.new_variable(0, 5, "x".to_string());
+        let y = solver.new_variable(0, 5, "y".to_string());
+        // b = 1 => x + y = 5, b = 0 => x - y = 1
+        solver.add_constraint(Box::new(IfThenElseConstraint::new(
+            b.clone(),
+            Rc::new(RefCell::new(SimpleArithmeticConstraint::new(
+                x.clone(),
+                y.clone(),
+                5,
+                true,
+            ))),
+            Rc::new(RefCell::new(SimpleArithmeticConstraint::new(
+                x.clone(),
+                y.clone(),
+                1,
+                false,
+            ))),
+        )));
+        (b, x, y)
+    };
+    let mut solver = new_solver();
+    model(&mut solver);
+    assert_eq!(solver.count_solutions(), 6 + 5);
+
+    // the assigned variables violate only the first branch, so b is 0
+    let mut solver = new_solver();
+    let (b, x, y) = model(&mut solver);
+    x.borrow_mut().assign(4);
+    y.borrow_mut().assign(3);
+    assert!(solver.propagate_all());
+    assert_eq!(b.borrow().try_value(), Some(0));
+
+    let mut solver = new_solver();
+    let (_, x, y) = model(&mut solver);
+    x.borrow_mut().assign(3);
+    y.borrow_mut().assign(3);
+    assert!(!solver.propagate_all());
+}
+
+#[test]
+fn test_overlap_pair_reified() {
+    let mut solver = new_solver();
+    let b = solver.new_variable(0, 1, "b".to_string());
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    // b = 1 <=> [x, x + 2) and [y, y + 1) overlap
+    let c = Rc::new(RefCell::new(OverlapPair::new(x.clone(), 2, y.clone(), 1)));
+    solver.add_constraint(Box::new(ReifiedConstraint::auto(b.clone(), c).unwrap()));
+    let solutions = solver.solve_all();
+    assert_eq!(solutions.len(), 16);
+    for s in solutions {
+        let overlap = s[1] <= s[2] && s[2] < s[1] + 2;
+        assert_eq!(s[0], overlap as i64);
+    }
+}
+
+#[test]
+fn test_reified_auto() {
+    let mut solver = new_solver();
+    let b = solver.new_variable(0, 1, "b".to_string());
+    let x = solver.new_variable(0, 3, "x".to_string());
+    let y = solver.new_variable(0, 3, "y".to_string());
+    // b = 1 <=> x + y <= 2
+    let c = Rc::new(RefCell::new(LinearInequalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        vec![1, 1],
+        2,
+    )));
+    let reified = ReifiedConstraint::auto(b.clone(), c).unwrap();
+    solver.add_constraint(Box::new(reified));
+    let solutions = solver.solve_all();
+    // b is determined by x and y
+    assert_eq!(solutions.len(), 16);
+    for s in solutions {
+        assert_eq!(s[0], (s[1] + s[2] <= 2) as i64);
+    }
+
+    // a negation holds exactly when the constraint is violated
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 2, "x".to_string());
+    let y = solver.new_variable(0, 2, "y".to_string());
+    let constraints: Vec<Box<dyn Constraint>> = vec![
+        Box::new(LexLessConstraint::new(vec![x.clone()], vec![y.clone()])),
+        Box::new(TableConstraint::new(
+            vec![x.clone(), y.clone()],
+            vec![vec![0, 1], vec![2, 2]],
+        )),
+    ];
+    for c in constraints {
+        let notc = c.negation().unwrap();
+        for (vx, vy) in [(0, 1), (1, 0), (2, 2), (1, 1)] {
+            x.borrow_mut().checkpoint();
+            y.borrow_mut().checkpoint();
+            x.borrow_mut().assign(vx);
+            y.borrow_mut().assign(vy);
+            assert_ne!(c.satisfied(), notc.borrow().satisfied());
+            x.borrow_mut().rollback();
+            y.borrow_mut().rollback();
+        }
+    }
+}
+
+#[test]
+fn test_bool_vars() {
+    // (a or b) and (not a or c) and (not b), so b is false, a and c are true
+    let mut solver = new_solver();
+    let a = solver.new_bool("a".to_string());
+    let b = solver.new_bool("b".to_string());
+    let c = solver.new_bool("c".to_string());
+    let not_a = solver.new_bool("not a".to_string());
+    let not_b = solver.new_bool("not b".to_string());
+    solver.add_constraint(Box::new(NegateConstraint::from_bools(a.clone(), not_a.clone())));
+    solver.add_constraint(Box::new(NegateConstraint::from_bools(b.clone(), not_b.clone())));
+    let clauses = vec![
+        vec![a.clone(), b.clone()],
+        vec![not_a, c.clone()],
+        vec![not_b],
+    ];
+    let mut clause_vars = Vec::new();
+    for (i, clause) in clauses.into_iter().enumerate() {
+        let cv = solver.new_bool(format!("clause_{}", i));
+        solver.add_constraint(Box::new(OrConstraint::from_bools(cv.clone(), clause)));
+        clause_vars.push(cv);
+    }
+    let all = BoolVar::new(solver.const_variable(1));
+    assert!(all.is_true() && all.is_fixed());
+    solver.add_constraint(Box::new(AndConstraint::from_bools(all, clause_vars)));
+    assert!(!a.is_fixed());
+    assert!(solver.solve());
+    assert!(a.is_true() && b.is_false() && c.is_true());
+    let var: Rc<RefCell<Variable>> = b.into();
+    assert_eq!(var.borrow().value(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_bool_var_domain() {
+    let mut solver = new_solver();
+    BoolVar::new(solver.new_variable(0, 2, "x".to_string()));
+}
+
+#[test]
+fn test_alldifferent_vertex_limit() {
+    let limited = || {
+        Solver::with_config(Config {
+            alldifferent_ac_max_vertices: Some(100),
+            ..Config::default()
+        })
+    };
+    // the value graph would have ten million vertices
+    let mut solver = limited();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    let y = solver.new_variable(0, 1, "y".to_string());
+    let z = solver.new_variable(0, 10_000_000, "z".to_string());
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![x, y, z.clone()])));
+    assert!(solver.propagate_all());
+    assert_eq!(z.borrow().get_lb(), 2);
+    assert_eq!(z.borrow().get_ub(), 10_000_000);
+
+    // values in the middle of the domain stay, only bounds are pruned
+    for (mut solver, pruned) in [(limited(), false), (new_solver(), true)] {
+        let x = solver.new_variable(5, 6, "x".to_string());
+        let y = solver.new_variable(5, 6, "y".to_string());
+        let z = solver.new_variable(0, 200, "z".to_string());
+        solver.add_constraint(Box::new(AllDifferentConstraint::new(vec![x, y, z.clone()])));
+        assert!(solver.propagate_all());
+        assert_eq!(z.borrow().possible(5), !pruned);
+        assert_eq!(z.borrow().possible(6), !pruned);
+    }
+
+    let mut solver = limited();
+    let vars: Vec<_> = (0..3)
+        .map(|i| solver.new_variable(0, 1_000, format!("x_{}", i)))
+        .collect();
+    for v in &vars[..2] {
+        v.borrow_mut().set_ub(0);
+    }
+    solver.add_constraint(Box::new(AllDifferentConstraint::new(vars)));
+    assert!(!solver.propagate_all());
+}
+
+#[test]
+fn test_gcc_capacity() {
+    // value 1 has cap 1, but both variables are forced to it
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    let y = solver.new_variable(0, 1, "y".to_string());
+    let card: HashMap<i64, usize> = [(1, 1)].into_iter().collect();
+    solver.add_constraint(Box::new(GlobalCardinalityConstraint::new(
+        vec![x.clone(), y.clone()],
+        card.clone(),
+    )));
+    assert!(!solver.propagate_all());
+
+    // with one variable the value without a count is removed
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 1, "x".to_string());
+    solver.add_constraint(Box::new(GlobalCardinalityConstraint::new(vec![x.clone()], card)));
+    assert!(solver.propagate_all());
+    assert_eq!(x.borrow().try_value(), Some(1));
+}
@@ -0,0 +1,189 @@
+use ezcp::events::Event;
+use ezcp::propagator::{Propagator, PropagatorControlBlock};
+use ezcp::solver::{Solver, SolverState};
+use ezcp::value_selector::MinValueSelector;
+use ezcp::variable::{domain_overlap, domains_disjoint, Variable};
+use ezcp::variable_selector::LexVariableSelector;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// counts its runs, woken up only by one event of x
+struct WatchingPropagator {
+    pcb: PropagatorControlBlock,
+    x: Rc<RefCell<Variable>>,
+    event: Event,
+    runs: usize,
+}
+
+impl Propagator for WatchingPropagator {
+    fn listen(&self, self_pointer: Rc<RefCell<dyn Propagator>>) {
+        self.x.borrow_mut().add_listener(self_pointer, self.event);
+    }
+
+    fn propagate(&mut self) {
+        self.runs += 1;
+    }
+
+    fn get_cb(&self) -> &PropagatorControlBlock {
+        &self.pcb
+    }
+
+    fn get_cb_mut(&mut self) -> &mut PropagatorControlBlock {
+        &mut self.pcb
+    }
+}
+
+fn new_solver() -> Solver {
+    Solver::new(
+        Box::new(LexVariableSelector {}),
+        Box::new(MinValueSelector {}),
+    )
+}
+
+fn watcher(
+    solver: &mut Solver,
+    x: &Rc<RefCell<Variable>>,
+    event: Event,
+) -> Rc<RefCell<WatchingPropagator>> {
+    let p = Rc::new(RefCell::new(WatchingPropagator {
+        pcb: PropagatorControlBlock::new(solver.new_propagator_id()),
+        x: x.clone(),
+        event,
+        runs: 0,
+    }));
+    solver.add_propagator(p.clone());
+    p.borrow().listen(p.clone());
+    p
+}
+
+#[test]
+fn test_tighten() {
+    for ub in [20, 500] {
+        let mut solver = new_solver();
+        let x = solver.new_variable(0, ub, "x".to_string());
+        let watchers: Vec<_> = [Event::LowerBound, Event::UpperBound, Event::Modified]
+            .into_iter()
+            .map(|event| watcher(&mut solver, &x, event))
+            .collect();
+        let runs = || -> Vec<_> { watchers.iter().map(|p| p.borrow().runs).collect() };
+        // the first pass runs everything once
+        assert!(solver.propagate_all());
+        assert_eq!(runs(), vec![1, 1, 1]);
+
+        assert!(x.borrow_mut().tighten(3, 17));
+        assert_eq!(x.borrow().get_lb(), 3);
+        assert_eq!(x.borrow().get_ub(), 17);
+        assert_eq!(x.borrow().size(), 15);
+        assert!(solver.propagate());
+        assert_eq!(runs(), vec![2, 2, 2]);
+
+        assert!(x.borrow_mut().tighten(3, 10));
+        assert_eq!(x.borrow().get_ub(), 10);
+        assert!(solver.propagate());
+        // lower bound did not change, so its watcher stays asleep
+        assert_eq!(runs(), vec![2, 3, 3]);
+
+        // nothing changes, nobody wakes up
+        assert!(x.borrow_mut().tighten(0, 15));
+        assert!(solver.propagate());
+        assert_eq!(runs(), vec![2, 3, 3]);
+
+        assert!(!x.borrow_mut().tighten(11, 20));
+    }
+}
+
+#[test]
+fn test_assign_bound_events() {
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 9, "x".to_string());
+    let lower = watcher(&mut solver, &x, Event::LowerBound);
+    let upper = watcher(&mut solver, &x, Event::UpperBound);
+    assert!(solver.propagate_all());
+    // only the lower bound moves
+    assert!(x.borrow_mut().assign(9));
+    assert!(solver.propagate());
+    assert_eq!(lower.borrow().runs, 2);
+    assert_eq!(upper.borrow().runs, 1);
+}
+
+#[test]
+fn test_try_value() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let x = Rc::new(RefCell::new(Variable::new(
+        solver_state,
+        -5,
+        5,
+        "x".to_string(),
+    )));
+    assert_eq!(x.borrow().try_value(), None);
+    x.borrow_mut().set_lb(5);
+    assert_eq!(x.borrow().try_value(), Some(5));
+
+    let y = Rc::new(RefCell::new(Variable::new(
+        x.borrow().solver_state.clone(),
+        0,
+        9,
+        "y".to_string(),
+    )));
+    assert_eq!(y.borrow().try_value(), None);
+    y.borrow_mut().assign(7);
+    assert_eq!(y.borrow().try_value(), Some(7));
+}
+
+#[test]
+fn test_domain_overlap() {
+    let solver_state = Rc::new(RefCell::new(SolverState::new()));
+    let var = |lb, ub, name: &str| Variable::new(solver_state.clone(), lb, ub, name.to_string());
+    let mut wide = var(-7, 200, "wide");
+    for x in (-7..=200).filter(|x| x % 3 == 0) {
+        wide.remove(x);
+    }
+    let mut small = var(130, 150, "small");
+    small.remove(131);
+    let other_wide = var(150, 400, "other_wide");
+    let far = var(300, 310, "far");
+    let naive = |a: &Variable, b: &Variable| a.iter().filter(|x| b.possible(*x)).count() as u64;
+
+    // overlapping, both bitmaps are shifted by a non-multiple of 64
+    assert_eq!(domain_overlap(&wide, &other_wide), naive(&wide, &other_wide));
+    assert_eq!(domain_overlap(&wide, &other_wide), 34);
+    assert!(!domains_disjoint(&wide, &other_wide));
+    // small is a subset of other_wide only at 150
+    assert_eq!(domain_overlap(&small, &other_wide), 1);
+    assert_eq!(domain_overlap(&wide, &small), naive(&wide, &small));
+    assert_eq!(domain_overlap(&small, &wide), naive(&wide, &small));
+    // subset
+    assert_eq!(domain_overlap(&far, &other_wide), far.size());
+    // disjoint
+    assert_eq!(domain_overlap(&far, &wide), 0);
+    assert!(domains_disjoint(&far, &wide));
+    assert!(domains_disjoint(&wide, &far));
+}
+
+#[test]
+fn test_assigned_event() {
+    let ops: [fn(&mut Variable) -> bool; 4] = [
+        |x| x.remove(1) && x.remove(2),
+        |x| x.set_lb(2),
+        |x| x.set_ub(0),
+        |x| x.tighten(1, 1),
+    ];
+    for op in ops {
+        let mut solver = new_solver();
+        let x = solver.new_variable(0, 2, "x".to_string());
+        let p = watcher(&mut solver, &x, Event::Assigned);
+        assert!(solver.propagate_all());
+        assert!(op(&mut x.borrow_mut()));
+        assert!(x.borrow().is_assigned());
+        assert!(solver.propagate());
+        assert_eq!(p.borrow().runs, 2);
+    }
+    // no event while several values are left
+    let mut solver = new_solver();
+    let x = solver.new_variable(0, 2, "x".to_string());
+    let p = watcher(&mut solver, &x, Event::Assigned);
+    assert!(solver.propagate_all());
+    assert!(x.borrow_mut().remove(0));
+    assert!(solver.propagate());
+    assert_eq!(p.borrow().runs, 1);
+}